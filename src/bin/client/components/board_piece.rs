@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+use real_time_chess::{Location, PlayerColor};
+
+/// a piece on the board. what kind of piece it is lives in the `ChessPiece` component next to
+/// this one so it can be swapped out on promotion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct BoardPiece {
+    pub color: PlayerColor,
+    pub square: Location,
+}
+
+/// the world space position of the center of a square.
+pub fn square_translation(square: &Location) -> Vec3 {
+    let rank: usize = square.0.into();
+    let file: usize = square.1.into();
+
+    Vec3::new((10 * rank) as f32 + 5.0, (10 * file) as f32 + 5.0, 1.0)
+}
@@ -0,0 +1,6 @@
+use crate::client::events::game_end::GameEnd;
+use bevy::prelude::*;
+
+/// how the last game ended. kept around so the post-game screen can show it.
+#[derive(Debug, Clone, Resource)]
+pub struct GameResult(pub GameEnd);
@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+use real_time_chess::Feature;
+
+/// how far along the handshake with the server is. nothing but the `Hello` is sent until the
+/// server accepts it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Resource)]
+pub enum Handshake {
+    #[default]
+    Pending,
+    /// the server accepted our `Hello` and will use these features.
+    Accepted { features: Vec<Feature> },
+}
+
+/// run condition for systems that have to wait until the server accepted our `Hello`.
+pub fn handshake_accepted(handshake: Res<Handshake>) -> bool {
+    matches!(*handshake, Handshake::Accepted { .. })
+}
@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+use real_time_chess::Location;
+use std::time::Instant;
+
+/// a piece that's travelling to `to`. its `BoardPiece` stays on the square it took off from
+/// until the server says it landed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct InFlight {
+    pub to: Location,
+    pub departed: Instant,
+    pub arrives: Instant,
+}
+
+impl InFlight {
+    /// how far along the trip the piece is, from 0 to 1.
+    pub fn progress(&self, now: Instant) -> f32 {
+        let total = self.arrives.saturating_duration_since(self.departed);
+
+        if total.is_zero() {
+            return 1.0;
+        }
+
+        (now.saturating_duration_since(self.departed).as_secs_f32() / total.as_secs_f32()).min(1.0)
+    }
+}
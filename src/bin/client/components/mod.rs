@@ -1,4 +1,14 @@
+pub mod board_piece;
 pub mod curent_client_id;
+pub mod game_result;
+pub mod handshake;
+pub mod in_flight;
 pub mod marker_components;
+pub mod pending_requests;
+pub mod protocol_error;
+pub mod queued_premoves;
+pub mod ready;
+pub mod rematch_requested;
 pub mod room_key;
+pub mod server_clock;
 pub mod system_message;
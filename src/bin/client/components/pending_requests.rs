@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use real_time_chess::{ClientChannel, ClientSystemMessage, RequestId, SystemRequest};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// how long to wait for the server to answer a request before trying again.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+/// how many times a request is sent before giving up on it.
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// a request the server hasn't answered yet.
+#[derive(Debug, Clone)]
+pub struct PendingRequest {
+    pub message: ClientSystemMessage,
+    /// when it was last sent.
+    pub sent: Instant,
+    pub attempts: u32,
+}
+
+/// keeps track of the requests sent on the system channel until the server answers them, so
+/// the UI can tell what it's still waiting on and requests that go unanswered can be retried.
+#[derive(Debug, Default, Resource)]
+pub struct PendingRequests {
+    next_id: u32,
+    pending: HashMap<RequestId, PendingRequest>,
+}
+
+impl PendingRequests {
+    /// sends `message` to the server and waits for the answer.
+    pub fn send(&mut self, client: &mut RenetClient, message: ClientSystemMessage) -> RequestId {
+        let id = RequestId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+
+        send_request(client, id, &message);
+        self.pending.insert(
+            id,
+            PendingRequest {
+                message,
+                sent: Instant::now(),
+                attempts: 1,
+            },
+        );
+
+        id
+    }
+
+    /// sends `message` to the server without waiting for the answer, for requests that are sent
+    /// often enough that a missing answer doesn't matter, like a `ClockPing`, or that are only
+    /// answered when they fail, like a `Rematch`.
+    pub fn send_untracked(&mut self, client: &mut RenetClient, message: ClientSystemMessage) {
+        let id = RequestId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+
+        send_request(client, id, &message);
+    }
+
+    /// the server answered `id`. returns the request it answered, unless it was already
+    /// answered or given up on.
+    pub fn resolve(&mut self, id: RequestId) -> Option<ClientSystemMessage> {
+        self.pending.remove(&id).map(|pending| pending.message)
+    }
+
+    /// true if a request matching `f` is still waiting for an answer, like a `JoinRoom` the
+    /// server hasn't let us into yet.
+    pub fn waiting_for(&self, f: impl Fn(&ClientSystemMessage) -> bool) -> bool {
+        self.pending.values().any(|pending| f(&pending.message))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&RequestId, &PendingRequest)> {
+        self.pending.iter()
+    }
+
+    /// sends the requests that have waited longer than `REQUEST_TIMEOUT` again, if it's safe to
+    /// send them twice. returns the ones that were given up on.
+    pub fn retry(&mut self, client: &mut RenetClient, now: Instant) -> Vec<ClientSystemMessage> {
+        let mut given_up = Vec::new();
+
+        self.pending.retain(|id, pending| {
+            if now.saturating_duration_since(pending.sent) < REQUEST_TIMEOUT {
+                return true;
+            }
+
+            if pending.attempts >= MAX_ATTEMPTS || !retriable(&pending.message) {
+                given_up.push(pending.message.clone());
+                return false;
+            }
+
+            send_request(client, *id, &pending.message);
+            pending.sent = now;
+            pending.attempts += 1;
+
+            true
+        });
+
+        given_up
+    }
+}
+
+/// true if the server answering the same request twice does no harm.
+fn retriable(message: &ClientSystemMessage) -> bool {
+    matches!(message, ClientSystemMessage::ListRooms)
+}
+
+fn send_request(client: &mut RenetClient, id: RequestId, message: &ClientSystemMessage) {
+    let request = SystemRequest::Request {
+        id,
+        message: message.clone(),
+    };
+    client.send_message(ClientChannel::System, bincode::serialize(&request).unwrap());
+}
@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+use real_time_chess::{DecodeError, HandshakeRejection};
+use std::fmt;
+
+/// why we stopped talking to the server, shown on the protocol mismatch screen.
+#[derive(Debug, Clone, Resource)]
+pub enum ProtocolError {
+    /// the server sent a message we couldn't decode.
+    Decode(DecodeError),
+    /// the server turned down our handshake.
+    Rejected(HandshakeRejection),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decode(e) => write!(f, "couldn't decode a message from the server. {e}"),
+            Self::Rejected(rejection) => write!(f, "the server turned us away. {rejection}"),
+        }
+    }
+}
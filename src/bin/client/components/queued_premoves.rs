@@ -0,0 +1,6 @@
+use bevy::prelude::*;
+use real_time_chess::Premove;
+
+/// the premoves the server is holding for us, drawn as arrows until they're made.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct QueuedPremoves(pub Vec<Premove>);
@@ -0,0 +1,6 @@
+use bevy::prelude::*;
+use real_time_chess::ReadyStage;
+
+/// a piece the server says has come off cooldown, so it can be flashed until it moves again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct Ready(pub ReadyStage);
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// we asked for a rematch and are waiting on the opponent. removed once the server seats us again.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct RematchRequested;
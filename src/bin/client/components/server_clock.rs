@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use real_time_chess::ServerTime;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// how many round trips are kept to work out the offset from.
+const SAMPLES: usize = 8;
+
+/// works out how far our clock is from the server's so the deadlines it sends can be turned
+/// into local times. every ping that comes back is a sample, and the one with the shortest
+/// round trip is trusted the most since it had the least time to be held up on the way.
+#[derive(Debug, Clone, Resource)]
+pub struct ServerClock {
+    epoch: Instant,
+    /// the round trip and the offset it measured, in nanoseconds from our clock to the server's.
+    samples: VecDeque<(Duration, i128)>,
+}
+
+impl Default for ServerClock {
+    fn default() -> Self {
+        Self {
+            epoch: Instant::now(),
+            samples: VecDeque::with_capacity(SAMPLES),
+        }
+    }
+}
+
+impl ServerClock {
+    /// our time, counted from when the clock was made. this is what goes into a ping.
+    pub fn local_time(&self) -> Duration {
+        self.epoch.elapsed()
+    }
+
+    /// how many pings have come back.
+    pub fn samples(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// records a pong for a ping sent at `sent` (our time) that the server answered at
+    /// `server_time`. the server is assumed to have answered half way through the round trip.
+    pub fn record(&mut self, sent: Duration, server_time: ServerTime) {
+        let rtt = self.local_time().saturating_sub(sent);
+        let offset = server_time.0.as_nanos() as i128 - (sent + rtt / 2).as_nanos() as i128;
+
+        if self.samples.len() == SAMPLES {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back((rtt, offset));
+    }
+
+    fn best(&self) -> Option<(Duration, i128)> {
+        self.samples.iter().min_by_key(|(rtt, _)| *rtt).copied()
+    }
+
+    /// the shortest round trip seen lately.
+    pub fn rtt(&self) -> Option<Duration> {
+        self.best().map(|(rtt, _)| rtt)
+    }
+
+    /// when `at` on the server's clock happens on ours. before the first pong comes back there's
+    /// nothing to go on, so everything is taken to happen right now.
+    pub fn to_local(&self, at: ServerTime) -> Instant {
+        let Some((_, offset)) = self.best() else {
+            return Instant::now();
+        };
+        let local = at.0.as_nanos() as i128 - offset;
+
+        if local < 0 {
+            self.epoch
+                .checked_sub(Duration::from_nanos(local.unsigned_abs() as u64))
+                .unwrap_or(self.epoch)
+        } else {
+            self.epoch + Duration::from_nanos(local as u64)
+        }
+    }
+}
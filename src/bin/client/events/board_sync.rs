@@ -0,0 +1,15 @@
+use crate::client::components::in_flight::InFlight;
+use bevy::prelude::*;
+use real_time_chess::{Location, PlacedPiece};
+use std::time::Instant;
+
+/// the server sent the whole board. whatever is on the board now should be replaced with it.
+#[derive(Debug, Clone, Event)]
+pub struct BoardSync {
+    pub pieces: Vec<PlacedPiece>,
+    /// the pieces that were still on cooldown when the snapshot was taken, and when they can
+    /// move again on our clock.
+    pub cooldowns: Vec<(Location, Instant)>,
+    /// the pieces that were travelling when the snapshot was taken.
+    pub flights: Vec<(PlacedPiece, InFlight)>,
+}
@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use real_time_chess::PlayerColor;
+use std::fmt;
 
 #[derive(Debug, Clone, Event)]
 pub enum GameEnd {
@@ -6,4 +8,25 @@ pub enum GameEnd {
     Loss,
     Draw,
     OpponentDisconnect,
+    /// the game we were watching ended with this color capturing the king.
+    Watched(PlayerColor),
+}
+
+impl GameEnd {
+    /// true if both players are still seated, so they can ask for a rematch.
+    pub fn can_rematch(&self) -> bool {
+        matches!(self, Self::Victory | Self::Loss | Self::Draw)
+    }
+}
+
+impl fmt::Display for GameEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Victory => write!(f, "you captured the king. you win!"),
+            Self::Loss => write!(f, "your king was captured. you lose."),
+            Self::Draw => write!(f, "the game ended in a draw."),
+            Self::OpponentDisconnect => write!(f, "your opponent disconnected. you win!"),
+            Self::Watched(winner) => write!(f, "{winner:?} captured the king."),
+        }
+    }
 }
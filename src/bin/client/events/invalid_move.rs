@@ -1,6 +1,24 @@
 use bevy::prelude::*;
+use real_time_chess::{Location, MoveRejection};
+use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Event)]
-pub struct InvalidMoveNotif {
-    pub message: String,
+/// one of our moves, or a move we saw, didn't happen.
+#[derive(Debug, Clone, PartialEq, Eq, Event)]
+pub enum InvalidMoveNotif {
+    /// the server turned down a move or premove we sent.
+    Rejected(MoveRejection),
+    /// opposing pieces moved to `to` at the same time and none of them moved.
+    Bounced { to: Location, from: Vec<Location> },
+}
+
+impl fmt::Display for InvalidMoveNotif {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rejected(rejection) => write!(f, "{rejection}"),
+            Self::Bounced { to, from } => write!(
+                f,
+                "the peices moving from {from:?} to {to:?} bounced off each other."
+            ),
+        }
+    }
 }
@@ -1,8 +1,14 @@
+pub mod board_sync;
 pub mod game_end;
 pub mod invalid_move;
 pub mod new_error;
 pub mod opponent_capture;
 pub mod opponent_move;
+pub mod piece_arrived;
+pub mod piece_ready;
+pub mod pieces_traded;
 pub mod player_capture;
 pub mod player_move;
+pub mod protocol_mismatch;
 pub mod room_change;
+pub mod seated;
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use real_time_chess::SystemError;
 
 #[derive(Debug, Clone, Event)]
-pub struct NewError(pub String);
+pub struct NewError(pub SystemError);
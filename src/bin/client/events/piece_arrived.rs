@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+use real_time_chess::{ChessPiece, Location};
+use std::time::Instant;
+
+/// a travelling piece landed on `to`.
+#[derive(Debug, Clone, Event)]
+pub struct PieceArrived {
+    /// the number the server gave the change. changes that arrive together are applied in this
+    /// order.
+    pub seq: u64,
+    pub from: Location,
+    pub to: Location,
+    /// the square a piece was captured on, if any.
+    pub captured_at: Option<Location>,
+    pub promotion: Option<ChessPiece>,
+    /// where the rook went when the king castled.
+    pub rook_move: Option<(Location, Location)>,
+    /// where the enemy pieces that were shot down took off from.
+    pub intercepted: Vec<Location>,
+    /// when the piece that landed can move again without a penalty, on our clock.
+    pub ready_at: Instant,
+}
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+use real_time_chess::{Location, ReadyStage};
+
+/// the server says the piece on `square` came off cooldown.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct PieceReady {
+    pub square: Location,
+    pub stage: ReadyStage,
+}
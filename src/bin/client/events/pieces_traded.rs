@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+use real_time_chess::Location;
+
+/// two opposing pieces captured each other at the same time. both should come off the board.
+#[derive(Debug, Clone, Event)]
+pub struct PiecesTraded {
+    /// the number the server gave the change. changes that arrive together are applied in this
+    /// order.
+    pub seq: u64,
+    pub squares: (Location, Location),
+}
@@ -1,11 +1,23 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use bevy::prelude::*;
-use real_time_chess::Location;
+use real_time_chess::{ChessPiece, Location};
 
 #[derive(Debug, Clone, Event)]
 pub struct PlayerMoveNotif {
+    /// the number the server gave the change. changes that arrive together are applied in this
+    /// order.
+    pub seq: u64,
     pub from: Location,
     pub to: Location,
+    /// the square a piece was captured on, if any.
+    pub captured_at: Option<Location>,
+    pub promotion: Option<ChessPiece>,
+    /// where the rook went when the king castled.
+    pub rook_move: Option<(Location, Location)>,
     pub cooldown: Duration,
+    /// when the moved piece can move again without a penalty, on our clock.
+    pub ready_at: Instant,
+    /// set when the piece is travelling. it lands once the server sends `PieceArrived`.
+    pub arrives_at: Option<Instant>,
 }
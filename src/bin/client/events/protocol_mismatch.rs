@@ -0,0 +1,7 @@
+use crate::client::components::protocol_error::ProtocolError;
+use bevy::prelude::*;
+
+/// we can't talk to the server, most likely because it's running a different version of the
+/// game.
+#[derive(Debug, Clone, Event)]
+pub struct ProtocolMismatch(pub ProtocolError);
@@ -1,8 +1,9 @@
 use bevy::prelude::*;
-use real_time_chess::RoomID;
+use real_time_chess::{RoomConfig, RoomID};
 
 #[derive(Debug, Clone, Event)]
 pub enum RoomChange {
-    Enter(RoomID),
+    /// joined a room played by the given rules.
+    Enter(RoomID, RoomConfig),
     Exit(RoomID),
 }
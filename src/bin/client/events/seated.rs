@@ -0,0 +1,6 @@
+use bevy::prelude::*;
+use real_time_chess::PlayerColor;
+
+/// the server gave us a seat in the room, playing as this color.
+#[derive(Debug, Clone, Event)]
+pub struct Seated(pub PlayerColor);
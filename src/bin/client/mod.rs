@@ -1,5 +1,6 @@
 pub mod components;
 pub mod events;
 pub mod plugins;
+pub mod settings;
 pub mod states;
 pub mod systems;
@@ -1,9 +1,12 @@
 use crate::client::{
+    components::queued_premoves::QueuedPremoves,
     states::game_state::GameState,
     systems::{
-        InGame, draw_game_board::draw_game_board, draw_pieces::draw_pieces, game_setup::game_setup,
-        load_game_assets::load_game_assets, setup_game_camera::setup_camera,
-        teardown_game::teardown_game,
+        InGame, animate_flights::animate_flights, draw_game_board::draw_game_board,
+        draw_pieces::draw_pieces, draw_premoves::draw_premoves, game_setup::game_setup,
+        handle_board_sync::handle_board_sync_events, handle_piece_move::handle_piece_move_events,
+        handle_piece_ready::handle_piece_ready_events, load_game_assets::load_game_assets,
+        setup_game_camera::setup_camera, teardown_game::teardown_game,
     },
 };
 use bevy::prelude::*;
@@ -12,7 +15,20 @@ pub struct InGamePlugin;
 
 impl Plugin for InGamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (draw_game_board, draw_pieces).in_set(InGame))
+        app.init_resource::<QueuedPremoves>()
+            .add_systems(
+                Update,
+                (
+                    draw_game_board,
+                    draw_pieces,
+                    draw_premoves,
+                    handle_board_sync_events.before(handle_piece_move_events),
+                    handle_piece_move_events,
+                    animate_flights.after(handle_piece_move_events),
+                    handle_piece_ready_events.after(handle_piece_move_events),
+                )
+                    .in_set(InGame),
+            )
             .add_systems(
                 OnEnter(GameState::InGame),
                 (setup_camera, game_setup, load_game_assets),
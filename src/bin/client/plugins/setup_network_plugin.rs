@@ -8,7 +8,10 @@ use bevy_renet::{
     renet::RenetClient,
 };
 use real_time_chess::{PROTOCOL_ID, connection_config};
-use std::{net::UdpSocket, time::SystemTime};
+use std::{
+    net::{SocketAddr, UdpSocket},
+    time::SystemTime,
+};
 
 /// If any error is found we just panic
 #[allow(clippy::never_loop)]
@@ -19,14 +22,18 @@ fn panic_on_error_system(mut renet_error: EventReader<NetcodeTransportError>) {
     }
 }
 
-pub struct SetupNetwork;
+/// connects to the server at `server_addr` from `bind_addr`.
+pub struct SetupNetwork {
+    pub server_addr: SocketAddr,
+    pub bind_addr: SocketAddr,
+}
 
 impl Plugin for SetupNetwork {
     fn build(&self, app: &mut App) {
         let client = RenetClient::new(connection_config());
 
-        let server_addr = "127.0.0.1:5000".parse().unwrap();
-        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let server_addr = self.server_addr;
+        let socket = UdpSocket::bind(self.bind_addr).unwrap();
         let current_time = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap();
@@ -0,0 +1,48 @@
+use real_time_chess::settings::{LogLevel, Settings, SettingsError, parse_flag};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+pub const USAGE: &str = "\
+usage: rt-chess [--config <file>] [--flag <value>]...
+
+flags:
+    --config <file>       read the settings from a json file first. flags override it.
+    --server <ip:port>    the server to connect to. (default 127.0.0.1:5000)
+    --port <port>         the port of the server, keeping its address.
+    --bind <ip:port>      the local address to connect from. port 0 lets the os pick one.
+                          (default 127.0.0.1:0)
+    --log-level <level>   error, warn, info, debug or trace. (default info)
+";
+
+/// where the client connects and how much it logs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientSettings {
+    pub server: SocketAddr,
+    pub bind: SocketAddr,
+    pub log_level: LogLevel,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        Self {
+            server: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 5000),
+            bind: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+            log_level: LogLevel::default(),
+        }
+    }
+}
+
+impl Settings for ClientSettings {
+    fn set(&mut self, flag: &str, value: &str) -> Result<(), SettingsError> {
+        match flag {
+            "server" => self.server = parse_flag(flag, value)?,
+            "port" => self.server.set_port(parse_flag(flag, value)?),
+            "bind" => self.bind = parse_flag(flag, value)?,
+            "log-level" => self.log_level = parse_flag(flag, value)?,
+            _ => return Err(SettingsError::UnknownFlag(format!("--{flag}"))),
+        }
+
+        Ok(())
+    }
+}
@@ -3,10 +3,14 @@ use bevy::prelude::*;
 #[derive(States, Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum GameState {
     InGame,
+    /// the game finished, showing the result.
+    PostGame,
     InRoom,
     #[default]
     Startup,
     RoomSelect,
     StartNewRoom,
     Settings,
+    /// the server sent a message we couldn't decode and we disconnected.
+    ProtocolMismatch,
 }
@@ -0,0 +1,17 @@
+use crate::client::components::{
+    board_piece::{BoardPiece, square_translation},
+    in_flight::InFlight,
+};
+use bevy::prelude::*;
+use std::time::Instant;
+
+/// slides travelling pieces from the square they took off from towards where they're headed.
+/// they stop at their destination and wait there until the server says they landed.
+pub fn animate_flights(mut pieces: Query<(&BoardPiece, &InFlight, &mut Transform)>) {
+    let now = Instant::now();
+
+    for (pos, flight, mut transform) in pieces.iter_mut() {
+        transform.translation = square_translation(&pos.square)
+            .lerp(square_translation(&flight.to), flight.progress(now));
+    }
+}
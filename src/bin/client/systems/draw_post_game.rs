@@ -0,0 +1,43 @@
+use crate::client::components::{
+    game_result::GameResult, pending_requests::PendingRequests, rematch_requested::RematchRequested,
+};
+use bevy::{app::AppExit, prelude::*};
+use bevy_egui::{EguiContexts, egui};
+use bevy_renet::renet::RenetClient;
+use real_time_chess::ClientSystemMessage;
+
+/// shows how the game ended and lets the players ask for a rematch. stays up until the server
+/// seats us for the next game.
+pub fn draw_post_game(
+    mut commands: Commands,
+    mut egui_contexts: EguiContexts,
+    mut client: ResMut<RenetClient>,
+    mut requests: ResMut<PendingRequests>,
+    result: Option<Res<GameResult>>,
+    rematch: Option<Res<RematchRequested>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    egui::CentralPanel::default().show(egui_contexts.ctx_mut(), |ui| {
+        ui.heading("game over");
+
+        let Some(GameResult(end)) = result.as_deref() else {
+            return;
+        };
+        ui.label(end.to_string());
+
+        if rematch.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("waiting for your opponent...");
+            });
+        } else if end.can_rematch() && ui.button("rematch").clicked() {
+            // only answered if it fails, the rematch itself shows up as a new seat.
+            requests.send_untracked(&mut client, ClientSystemMessage::Rematch);
+            commands.insert_resource(RematchRequested);
+        }
+
+        if ui.button("quit").clicked() {
+            exit.send(AppExit::Success);
+        }
+    });
+}
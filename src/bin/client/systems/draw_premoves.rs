@@ -0,0 +1,13 @@
+use crate::client::components::{board_piece::square_translation, queued_premoves::QueuedPremoves};
+use bevy::prelude::*;
+
+/// draws an arrow for every queued premove, from the piece to where it'll move.
+pub fn draw_premoves(mut gizmos: Gizmos, premoves: Res<QueuedPremoves>) {
+    for premove in premoves.0.iter() {
+        gizmos.arrow_2d(
+            square_translation(&premove.from).truncate(),
+            square_translation(&premove.to).truncate(),
+            Color::srgba(0.3, 0.6, 1.0, 0.8),
+        );
+    }
+}
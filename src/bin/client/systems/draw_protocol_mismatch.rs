@@ -0,0 +1,26 @@
+use crate::client::components::protocol_error::ProtocolError;
+use bevy::{app::AppExit, prelude::*};
+use bevy_egui::{EguiContexts, egui};
+
+/// tells the player the server is speaking a different protocol and lets them quit.
+pub fn draw_protocol_mismatch(
+    mut egui_contexts: EguiContexts,
+    error: Option<Res<ProtocolError>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    egui::CentralPanel::default().show(egui_contexts.ctx_mut(), |ui| {
+        ui.heading("protocol mismatch");
+        ui.label(
+            "the server sent something this version of rt-chess doesn't understand. make sure \
+             you and the server are running the same version.",
+        );
+
+        if let Some(error) = error {
+            ui.label(error.to_string());
+        }
+
+        if ui.button("quit").clicked() {
+            exit.send(AppExit::Success);
+        }
+    });
+}
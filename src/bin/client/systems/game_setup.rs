@@ -1,5 +1,23 @@
+use crate::client::components::board_piece::{BoardPiece, square_translation};
 use bevy::prelude::*;
+use real_time_chess::{PlacedPiece, rules::Board};
 
-pub fn game_setup(commands: Commands) {
-    // commands.init_resource::<Room>();
+/// spawns the pieces in their starting positions.
+pub fn game_setup(mut commands: Commands) {
+    for placed in Board::default().placed_pieces() {
+        spawn_board_piece(&mut commands, &placed);
+    }
+}
+
+pub fn spawn_board_piece(commands: &mut Commands, placed: &PlacedPiece) -> Entity {
+    commands
+        .spawn((
+            placed.piece,
+            BoardPiece {
+                color: placed.color,
+                square: placed.pos,
+            },
+            Transform::from_translation(square_translation(&placed.pos)),
+        ))
+        .id()
 }
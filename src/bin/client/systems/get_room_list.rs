@@ -1,10 +1,8 @@
+use crate::client::components::pending_requests::PendingRequests;
 use bevy::prelude::*;
 use bevy_renet::renet::RenetClient;
-use real_time_chess::{ClientChannel, ClientSystemMessage};
+use real_time_chess::ClientSystemMessage;
 
-pub fn get_rooms_list(mut client: ResMut<RenetClient>) {
-    client.send_message(
-        ClientChannel::System,
-        bincode::serialize(&ClientSystemMessage::ListRooms).unwrap(),
-    )
+pub fn get_rooms_list(mut client: ResMut<RenetClient>, mut requests: ResMut<PendingRequests>) {
+    requests.send(&mut client, ClientSystemMessage::ListRooms);
 }
@@ -0,0 +1,35 @@
+use crate::client::{
+    components::board_piece::BoardPiece, events::board_sync::BoardSync,
+    systems::game_setup::spawn_board_piece,
+};
+use bevy::prelude::*;
+
+/// throws away the pieces on the board and lays out the ones the server sent instead.
+pub fn handle_board_sync_events(
+    mut commands: Commands,
+    mut board_sync_event: EventReader<BoardSync>,
+    pieces: Query<Entity, With<BoardPiece>>,
+) {
+    let Some(BoardSync {
+        pieces: placed_pieces,
+        flights,
+        ..
+    }) = board_sync_event.read().last()
+    else {
+        return;
+    };
+
+    for piece in pieces.iter() {
+        commands.entity(piece).despawn_recursive();
+    }
+
+    for placed in placed_pieces {
+        spawn_board_piece(&mut commands, placed);
+    }
+
+    for (piece, flight) in flights {
+        let entity = spawn_board_piece(&mut commands, piece);
+
+        commands.entity(entity).insert(*flight);
+    }
+}
@@ -9,7 +9,7 @@ pub fn handle_error_event(mut commands: Commands, mut error_event: EventReader<N
     for ev in error_event.read() {
         commands.spawn(SystemMessage {
             display_duration: Duration::from_secs_f32(2.5),
-            message: ev.0.clone(),
+            message: ev.0.to_string(),
             msg_type: SystemMessageType::MiscError,
             shown: None,
         });
@@ -0,0 +1,27 @@
+use crate::client::{
+    components::{
+        game_result::GameResult,
+        system_message::{SystemMessage, SystemMessageType},
+    },
+    events::game_end::GameEnd,
+    states::game_state::GameState,
+};
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub fn handle_game_end_event(
+    mut commands: Commands,
+    mut game_end_event: EventReader<GameEnd>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for ev in game_end_event.read() {
+        commands.spawn(SystemMessage {
+            display_duration: Duration::from_secs_f32(5.0),
+            message: ev.to_string(),
+            msg_type: SystemMessageType::Alert,
+            shown: None,
+        });
+        commands.insert_resource(GameResult(ev.clone()));
+        next_state.set(GameState::PostGame);
+    }
+}
@@ -13,7 +13,7 @@ pub fn handle_invalid_move_event(
     for ev in invalid_event.read() {
         commands.spawn(SystemMessage {
             display_duration: Duration::from_secs_f32(3.5),
-            message: ev.message.clone(),
+            message: ev.to_string(),
             msg_type: SystemMessageType::InvalidMove,
             shown: None,
         });
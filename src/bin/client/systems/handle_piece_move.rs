@@ -0,0 +1,251 @@
+use crate::client::{
+    components::{
+        board_piece::{BoardPiece, square_translation},
+        in_flight::InFlight,
+        ready::Ready,
+    },
+    events::{
+        opponent_move::OpponentMoveNotif, piece_arrived::PieceArrived, pieces_traded::PiecesTraded,
+        player_move::PlayerMoveNotif,
+    },
+};
+use bevy::prelude::*;
+use real_time_chess::{ChessPiece, Location};
+use std::{collections::HashMap, time::Instant};
+
+type Pieces<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut BoardPiece,
+        &'static mut ChessPiece,
+        &'static mut Transform,
+        Has<InFlight>,
+    ),
+>;
+
+/// a move the server accepted, whoever made it.
+struct Move {
+    from: Location,
+    to: Location,
+    captured_at: Option<Location>,
+    promotion: Option<ChessPiece>,
+    rook_move: Option<(Location, Location)>,
+    arrives_at: Option<Instant>,
+}
+
+/// a change to the board, in the order the server made them.
+enum Change<'a> {
+    Moved(Move),
+    Traded(&'a PiecesTraded),
+    Arrived(&'a PieceArrived),
+}
+
+/// where every piece is while the changes of a frame are applied. commands only run at the end
+/// of the frame and the query is changed as we go, so pieces are looked up here instead.
+struct Squares {
+    /// pieces standing on the board.
+    standing: HashMap<Location, Entity>,
+    /// travelling pieces, by the square they took off from.
+    flying: HashMap<Location, Entity>,
+}
+
+impl Squares {
+    fn new(pieces: &Pieces) -> Self {
+        let mut squares = Self {
+            standing: HashMap::new(),
+            flying: HashMap::new(),
+        };
+
+        for (entity, pos, _, _, flying) in pieces.iter() {
+            if flying {
+                squares.flying.insert(pos.square, entity);
+            } else {
+                squares.standing.insert(pos.square, entity);
+            }
+        }
+
+        squares
+    }
+}
+
+/// moves pieces around the board as the server accepts moves, removing captured pieces,
+/// swapping promoted pawns for their new piece and moving the rook along when a king castles.
+/// pieces that traded with each other are both removed. travelling pieces are marked
+/// `InFlight` when they take off and only moved once they land. moved pieces aren't `Ready`
+/// anymore. changes are applied in the order the server numbered them, since a piece can move
+/// onto a square another one left in the same batch.
+pub fn handle_piece_move_events(
+    mut commands: Commands,
+    mut player_move_event: EventReader<PlayerMoveNotif>,
+    mut opponent_move_event: EventReader<OpponentMoveNotif>,
+    mut traded_event: EventReader<PiecesTraded>,
+    mut arrived_event: EventReader<PieceArrived>,
+    mut pieces: Pieces,
+) {
+    let mut changes: Vec<(u64, Change)> = player_move_event
+        .read()
+        .map(|ev| {
+            (
+                ev.seq,
+                Change::Moved(Move {
+                    from: ev.from,
+                    to: ev.to,
+                    captured_at: ev.captured_at,
+                    promotion: ev.promotion,
+                    rook_move: ev.rook_move,
+                    arrives_at: ev.arrives_at,
+                }),
+            )
+        })
+        .chain(opponent_move_event.read().map(|ev| {
+            (
+                ev.seq,
+                Change::Moved(Move {
+                    from: ev.from,
+                    to: ev.to,
+                    captured_at: ev.captured_at,
+                    promotion: ev.promotion,
+                    rook_move: ev.rook_move,
+                    arrives_at: ev.arrives_at,
+                }),
+            )
+        }))
+        .chain(traded_event.read().map(|ev| (ev.seq, Change::Traded(ev))))
+        .chain(arrived_event.read().map(|ev| (ev.seq, Change::Arrived(ev))))
+        .collect();
+    changes.sort_by_key(|(seq, _)| *seq);
+
+    let mut squares = Squares::new(&pieces);
+
+    for (_, change) in changes {
+        match change {
+            Change::Moved(Move {
+                from,
+                to,
+                arrives_at: Some(arrives),
+                ..
+            }) => match squares.standing.remove(&from) {
+                Some(entity) => {
+                    squares.flying.insert(from, entity);
+                    commands.entity(entity).remove::<Ready>().insert(InFlight {
+                        to,
+                        departed: Instant::now(),
+                        arrives,
+                    });
+                }
+                None => warn!("the server moved a piece from {from:?} but there isn't one there."),
+            },
+            Change::Moved(Move {
+                from,
+                to,
+                captured_at,
+                promotion,
+                rook_move,
+                arrives_at: None,
+            }) => {
+                capture(&mut commands, &mut squares, captured_at);
+                let moved = move_piece(&mut pieces, &mut squares, from, to, promotion);
+                let rook = rook_move.and_then(|(rook_from, rook_to)| {
+                    move_piece(&mut pieces, &mut squares, rook_from, rook_to, None)
+                });
+
+                for entity in moved.into_iter().chain(rook) {
+                    commands.entity(entity).remove::<Ready>();
+                }
+            }
+            Change::Traded(PiecesTraded {
+                squares: (a, b), ..
+            }) => {
+                for square in [a, b] {
+                    let traded = squares.standing.remove(square);
+
+                    for entity in traded.into_iter().chain(squares.flying.remove(square)) {
+                        commands.entity(entity).despawn_recursive();
+                    }
+                }
+            }
+            Change::Arrived(arrived) => {
+                for square in &arrived.intercepted {
+                    if let Some(entity) = squares.flying.remove(square) {
+                        commands.entity(entity).despawn_recursive();
+                    }
+                }
+
+                capture(&mut commands, &mut squares, arrived.captured_at);
+
+                let Some(entity) = squares.flying.remove(&arrived.from) else {
+                    warn!(
+                        "the server landed a piece from {:?} but there isn't one in the air.",
+                        arrived.from
+                    );
+                    continue;
+                };
+
+                place(
+                    &mut pieces,
+                    &mut squares,
+                    entity,
+                    arrived.to,
+                    arrived.promotion,
+                );
+                commands.entity(entity).remove::<InFlight>();
+
+                if let Some((rook_from, rook_to)) = arrived.rook_move
+                    && let Some(rook) =
+                        move_piece(&mut pieces, &mut squares, rook_from, rook_to, None)
+                {
+                    commands.entity(rook).remove::<Ready>();
+                }
+            }
+        }
+    }
+}
+
+/// removes the piece standing on `captured_at`. travelling pieces aren't standing anywhere so
+/// they can't be captured this way.
+fn capture(commands: &mut Commands, squares: &mut Squares, captured_at: Option<Location>) {
+    if let Some(entity) = captured_at.and_then(|captured_at| squares.standing.remove(&captured_at))
+    {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// moves the piece standing on `from` to `to`.
+fn move_piece(
+    pieces: &mut Pieces,
+    squares: &mut Squares,
+    from: Location,
+    to: Location,
+    promotion: Option<ChessPiece>,
+) -> Option<Entity> {
+    let Some(entity) = squares.standing.remove(&from) else {
+        warn!("the server moved a piece from {from:?} but there isn't one there.");
+        return None;
+    };
+
+    place(pieces, squares, entity, to, promotion);
+
+    Some(entity)
+}
+
+/// puts `entity` down on `to`.
+fn place(
+    pieces: &mut Pieces,
+    squares: &mut Squares,
+    entity: Entity,
+    to: Location,
+    promotion: Option<ChessPiece>,
+) {
+    squares.standing.insert(to, entity);
+
+    if let Ok((_, mut pos, mut piece, mut transform, _)) = pieces.get_mut(entity) {
+        pos.square = to;
+        transform.translation = square_translation(&to);
+
+        if let Some(promotion) = promotion {
+            *piece = promotion;
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::client::{
+    components::{board_piece::BoardPiece, in_flight::InFlight, ready::Ready},
+    events::piece_ready::PieceReady,
+};
+use bevy::prelude::*;
+
+/// marks pieces that came off cooldown as `Ready`.
+pub fn handle_piece_ready_events(
+    mut commands: Commands,
+    mut piece_ready_event: EventReader<PieceReady>,
+    pieces: Query<(Entity, &BoardPiece), Without<InFlight>>,
+) {
+    for ready in piece_ready_event.read() {
+        match pieces.iter().find(|(_, pos)| pos.square == ready.square) {
+            Some((entity, _)) => {
+                commands.entity(entity).insert(Ready(ready.stage));
+            }
+            None => warn!(
+                "the server says a piece on {:?} is ready but there isn't one there.",
+                ready.square
+            ),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::client::{events::protocol_mismatch::ProtocolMismatch, states::game_state::GameState};
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+
+/// gives up on a server we can't understand, or that won't talk to us. there's no telling what
+/// state we're in after a message we couldn't read, so we disconnect and show the protocol
+/// mismatch screen.
+pub fn handle_protocol_mismatch_event(
+    mut commands: Commands,
+    mut mismatch_event: EventReader<ProtocolMismatch>,
+    mut client: ResMut<RenetClient>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(ProtocolMismatch(e)) = mismatch_event.read().next() else {
+        return;
+    };
+
+    error!("{e}");
+    client.disconnect();
+    commands.insert_resource(e.clone());
+    next_state.set(GameState::ProtocolMismatch);
+}
@@ -13,8 +13,8 @@ pub fn handle_room_change_event(
 ) {
     for ev in invalid_event.read() {
         let (message, msg_type) = match ev {
-            RoomChange::Enter(id) => (
-                format!("joined room: {}", display_room_id(id)),
+            RoomChange::Enter(id, config) => (
+                format!("joined room: {} ({config})", display_room_id(id)),
                 SystemMessageType::RoomJoin,
             ),
             RoomChange::Exit(id) => (
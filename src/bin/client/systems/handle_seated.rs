@@ -0,0 +1,26 @@
+use crate::client::{
+    components::{game_result::GameResult, rematch_requested::RematchRequested},
+    events::seated::Seated,
+    states::game_state::GameState,
+};
+use bevy::prelude::*;
+
+/// remembers which color we play as and starts the game. a seat arriving after a game ended
+/// means a rematch started.
+pub fn handle_seated_event(
+    mut commands: Commands,
+    mut seated_event: EventReader<Seated>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for Seated(color) in seated_event.read() {
+        info!("seated as {color:?}.");
+        commands.insert_resource(*color);
+        commands.remove_resource::<GameResult>();
+        commands.remove_resource::<RematchRequested>();
+
+        if *state.get() != GameState::InGame {
+            next_state.set(GameState::InGame);
+        }
+    }
+}
@@ -1,18 +1,31 @@
 use bevy::prelude::*;
 
+pub mod animate_flights;
 pub mod draw_game_board;
 pub mod draw_pieces;
+pub mod draw_post_game;
+pub mod draw_premoves;
+pub mod draw_protocol_mismatch;
 pub mod enter_room_select;
 pub mod game_setup;
 pub mod get_room_list;
+pub mod handle_board_sync;
 pub mod handle_error;
+pub mod handle_game_end;
 pub mod handle_invalid_move;
+pub mod handle_piece_move;
+pub mod handle_piece_ready;
+pub mod handle_protocol_mismatch;
 pub mod handle_room_change;
+pub mod handle_seated;
 pub mod load_game_assets;
 pub mod recv_in_game_messages;
 pub mod recv_in_room_messages;
 pub mod recv_system_messages;
+pub mod retry_requests;
+pub mod send_hello;
 pub mod setup_game_camera;
+pub mod sync_server_clock;
 pub mod teardown_game;
 pub mod update_visualizer;
 
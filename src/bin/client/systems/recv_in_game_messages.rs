@@ -1,80 +1,240 @@
-use crate::client::events::{
-    game_end::GameEnd, invalid_move::InvalidMoveNotif, opponent_capture::OpponentCaptureNotif,
-    opponent_move::OpponentMoveNotif, player_capture::PlayerCaptureNotif,
-    player_move::PlayerMoveNotif,
+use crate::client::{
+    components::{
+        in_flight::InFlight, protocol_error::ProtocolError, queued_premoves::QueuedPremoves,
+        server_clock::ServerClock,
+    },
+    events::{
+        board_sync::BoardSync, game_end::GameEnd, invalid_move::InvalidMoveNotif,
+        opponent_capture::OpponentCaptureNotif, opponent_move::OpponentMoveNotif,
+        piece_arrived::PieceArrived, piece_ready::PieceReady, pieces_traded::PiecesTraded,
+        player_capture::PlayerCaptureNotif, player_move::PlayerMoveNotif,
+        protocol_mismatch::ProtocolMismatch, seated::Seated,
+    },
 };
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_renet::renet::RenetClient;
-use real_time_chess::{PlayerColor, ServerChannel, ServerInGameMessage};
+use real_time_chess::{
+    ClientChannel, ClientInGameMessage, PlayerColor, ServerChannel, ServerInGameMessage, decode,
+};
+
+/// everything `recv_in_game_messages` can tell the rest of the game about. bevy systems can't
+/// take more than 16 parameters, so the event writers are bundled up here.
+#[derive(SystemParam)]
+pub struct InGameEvents<'w> {
+    invalid_message_event: EventWriter<'w, InvalidMoveNotif>,
+    pl_capture_event: EventWriter<'w, PlayerCaptureNotif>,
+    op_capture_event: EventWriter<'w, OpponentCaptureNotif>,
+    player_move_event: EventWriter<'w, PlayerMoveNotif>,
+    opponent_move_event: EventWriter<'w, OpponentMoveNotif>,
+    game_over_event: EventWriter<'w, GameEnd>,
+    seated_event: EventWriter<'w, Seated>,
+    board_sync_event: EventWriter<'w, BoardSync>,
+    traded_event: EventWriter<'w, PiecesTraded>,
+    arrived_event: EventWriter<'w, PieceArrived>,
+    ready_event: EventWriter<'w, PieceReady>,
+    mismatch_event: EventWriter<'w, ProtocolMismatch>,
+}
 
 pub fn recv_in_game_messages(
     mut client: ResMut<RenetClient>,
     player_color: Option<Res<PlayerColor>>,
-    mut invalid_message_event: EventWriter<InvalidMoveNotif>,
-    mut pl_capture_event: EventWriter<PlayerCaptureNotif>,
-    mut op_capture_event: EventWriter<OpponentCaptureNotif>,
-    mut player_move_event: EventWriter<PlayerMoveNotif>,
-    mut opponent_move_event: EventWriter<OpponentMoveNotif>,
-    mut game_over_event: EventWriter<GameEnd>,
+    server_clock: Res<ServerClock>,
+    mut events: InGameEvents,
+    mut premoves: ResMut<QueuedPremoves>,
+    mut last_seq: Local<Option<u64>>,
 ) {
     // let client_id = client_id.0;
     while let Some(message) = client.receive_message(ServerChannel::InGame) {
-        let server_message = bincode::deserialize(&message).unwrap();
+        let server_message = match decode(&message) {
+            Ok(message) => message,
+            Err(e) => {
+                events
+                    .mismatch_event
+                    .send(ProtocolMismatch(ProtocolError::Decode(e)));
+                return;
+            }
+        };
         match server_message {
-            ServerInGameMessage::InvalidMove(message) => {
+            ServerInGameMessage::Seated(color) => {
+                events.seated_event.send(Seated(color));
+            }
+            ServerInGameMessage::BoardSnapshot {
+                seq,
+                pieces,
+                cooldowns,
+                flights,
+            } => {
+                *last_seq = Some(seq);
+                events.board_sync_event.send(BoardSync {
+                    pieces,
+                    cooldowns: cooldowns
+                        .into_iter()
+                        .map(|cooldown| (cooldown.pos, server_clock.to_local(cooldown.ready_at)))
+                        .collect(),
+                    flights: flights
+                        .into_iter()
+                        .map(|flight| {
+                            (
+                                flight.piece,
+                                InFlight {
+                                    to: flight.to,
+                                    departed: server_clock.to_local(flight.departed),
+                                    arrives: server_clock.to_local(flight.arrives_at),
+                                },
+                            )
+                        })
+                        .collect(),
+                });
+            }
+            ServerInGameMessage::Premoves(queued) => {
+                premoves.0 = queued;
+            }
+            ServerInGameMessage::Bounced { to, from } => {
+                events
+                    .invalid_message_event
+                    .send(InvalidMoveNotif::Bounced { to, from });
+            }
+            ServerInGameMessage::Traded { seq, squares } => {
+                if in_sequence(&mut client, &mut last_seq, seq) {
+                    events.traded_event.send(PiecesTraded { seq, squares });
+                }
+            }
+            ServerInGameMessage::InvalidMove(rejection) => {
                 if player_color.as_ref().is_none() {
                     continue;
                 }
 
                 // TODO: use system message.
-                invalid_message_event.send(InvalidMoveNotif { message });
+                events
+                    .invalid_message_event
+                    .send(InvalidMoveNotif::Rejected(rejection));
             }
             ServerInGameMessage::MoveRecv {
+                seq,
                 player,
                 from,
                 to,
                 capture,
+                captured_at,
+                promotion,
+                rook_move,
                 cooldown,
+                ready_at,
+                arrives_at,
             } => {
-                if player_color.as_ref().is_none() {
+                if !in_sequence(&mut client, &mut last_seq, seq) {
                     continue;
                 }
 
+                let ready_at = server_clock.to_local(ready_at);
+                let arrives_at = arrives_at.map(|arrives_at| server_clock.to_local(arrives_at));
+
+                // observers don't have a color so every move is someone else's to them.
                 let my_move = player_color.as_ref().is_some_and(|color| **color == player);
 
                 if capture && my_move {
                     // TODO: send player capture notification.
-                    // pl_capture_event.send(PlayerCaptureNotif { piece: , square: to });
+                    // events.pl_capture_event.send(PlayerCaptureNotif { piece: , square: to });
                 } else if capture && !my_move {
                     // TODO: send opponent capture notification.
-                    // op_capture_event.send_default();
+                    // events.op_capture_event.send_default();
                 }
 
                 if my_move {
-                    player_move_event.send(PlayerMoveNotif { from, to, cooldown });
+                    events.player_move_event.send(PlayerMoveNotif {
+                        seq,
+                        from,
+                        to,
+                        captured_at,
+                        promotion,
+                        rook_move,
+                        cooldown,
+                        ready_at,
+                        arrives_at,
+                    });
                 } else {
-                    opponent_move_event.send(OpponentMoveNotif { from, to, cooldown });
+                    events.opponent_move_event.send(OpponentMoveNotif {
+                        seq,
+                        from,
+                        to,
+                        captured_at,
+                        promotion,
+                        rook_move,
+                        cooldown,
+                        ready_at,
+                        arrives_at,
+                    });
                 }
             }
+            ServerInGameMessage::Arrived {
+                seq,
+                from,
+                to,
+                captured_at,
+                promotion,
+                rook_move,
+                intercepted,
+                ready_at,
+                ..
+            } => {
+                if in_sequence(&mut client, &mut last_seq, seq) {
+                    events.arrived_event.send(PieceArrived {
+                        seq,
+                        from,
+                        to,
+                        captured_at,
+                        promotion,
+                        rook_move,
+                        intercepted,
+                        ready_at: server_clock.to_local(ready_at),
+                    });
+                }
+            }
+            ServerInGameMessage::PieceReady { square, stage } => {
+                events.ready_event.send(PieceReady { square, stage });
+            }
             ServerInGameMessage::Victory(player) => {
                 if player_color.as_ref().is_none() {
+                    events.game_over_event.send(GameEnd::Watched(player));
                     continue;
                 }
 
                 let my_vic = player_color.as_ref().is_some_and(|color| **color == player);
 
                 if my_vic {
-                    game_over_event.send(GameEnd::Victory);
+                    events.game_over_event.send(GameEnd::Victory);
                 } else {
-                    game_over_event.send(GameEnd::Loss);
+                    events.game_over_event.send(GameEnd::Loss);
                 }
             }
             ServerInGameMessage::Draw => {
-                game_over_event.send(GameEnd::Draw);
+                events.game_over_event.send(GameEnd::Draw);
             }
             ServerInGameMessage::OpponentDisconect => {
-                game_over_event.send(GameEnd::OpponentDisconnect);
+                events.game_over_event.send(GameEnd::OpponentDisconnect);
             }
         }
     }
 }
+
+/// checks that `seq` comes right after the last change we saw. if it doesn't a change was missed
+/// so a snapshot is requested instead.
+fn in_sequence(client: &mut RenetClient, last_seq: &mut Option<u64>, seq: u64) -> bool {
+    if let Some(expected) = last_seq
+        .map(|last| last + 1)
+        .filter(|expected| *expected != seq)
+    {
+        warn!("missed a move, expected #{expected} but got #{seq}.");
+        client.send_message(
+            ClientChannel::Game,
+            bincode::serialize(&ClientInGameMessage::RequestSnapshot).unwrap(),
+        );
+        *last_seq = None;
+
+        return false;
+    }
+
+    *last_seq = Some(seq);
+
+    true
+}
@@ -1,10 +1,22 @@
+use crate::client::{
+    components::protocol_error::ProtocolError, events::protocol_mismatch::ProtocolMismatch,
+};
 use bevy::prelude::*;
 use bevy_renet::renet::RenetClient;
-use real_time_chess::{ServerChannel, ServerInRoomMessage};
+use real_time_chess::{ServerChannel, ServerInRoomMessage, decode};
 
-pub fn recv_in_room_messages(mut client: ResMut<RenetClient>) {
+pub fn recv_in_room_messages(
+    mut client: ResMut<RenetClient>,
+    mut mismatch_event: EventWriter<ProtocolMismatch>,
+) {
     while let Some(message) = client.receive_message(ServerChannel::InRoom) {
-        let server_message = bincode::deserialize(&message).unwrap();
+        let server_message = match decode(&message) {
+            Ok(message) => message,
+            Err(e) => {
+                mismatch_event.send(ProtocolMismatch(ProtocolError::Decode(e)));
+                return;
+            }
+        };
         match server_message {
             ServerInRoomMessage::WaitingForPlayers => {
                 // TODO: switch to a "loading game" state.
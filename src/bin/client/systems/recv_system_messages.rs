@@ -1,34 +1,76 @@
 use crate::client::{
-    components::room_key::RoomKey,
-    events::{new_error::NewError, room_change::RoomChange},
+    components::{
+        handshake::Handshake, pending_requests::PendingRequests, protocol_error::ProtocolError,
+        room_key::RoomKey, server_clock::ServerClock,
+    },
+    events::{new_error::NewError, protocol_mismatch::ProtocolMismatch, room_change::RoomChange},
 };
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_renet::renet::RenetClient;
-use real_time_chess::{ServerChannel, ServerSystemMessage};
+use real_time_chess::{HandshakeReply, ServerChannel, ServerSystemMessage, SystemResponse, decode};
+
+/// everything `recv_system_messages` can tell the rest of the game about.
+#[derive(SystemParam)]
+pub struct SystemEvents<'w> {
+    error_event: EventWriter<'w, NewError>,
+    room_change_event: EventWriter<'w, RoomChange>,
+    mismatch_event: EventWriter<'w, ProtocolMismatch>,
+}
 
 pub fn recv_system_messages(
     mut commands: Commands,
     mut client: ResMut<RenetClient>,
-    mut error_event: EventWriter<NewError>,
-    mut room_change_event: EventWriter<RoomChange>,
+    mut server_clock: ResMut<ServerClock>,
+    mut handshake: ResMut<Handshake>,
+    mut requests: ResMut<PendingRequests>,
+    mut events: SystemEvents,
 ) {
     // let client_id = client_id.0;
     while let Some(message) = client.receive_message(ServerChannel::System) {
-        let server_message = bincode::deserialize(&message).unwrap();
+        let (request, server_message) = match decode(&message) {
+            Ok(SystemResponse::Handshake(reply)) => (None, ServerSystemMessage::Handshake(reply)),
+            Ok(SystemResponse::Reply { request, message }) => (request, message),
+            Err(e) => {
+                events
+                    .mismatch_event
+                    .send(ProtocolMismatch(ProtocolError::Decode(e)));
+                return;
+            }
+        };
+
+        if let Some(request) = request {
+            requests.resolve(request);
+        }
+
         match server_message {
-            ServerSystemMessage::ListRooms(room_ids) => {
-                for id in room_ids {
-                    commands.spawn(RoomKey(id));
+            ServerSystemMessage::Handshake(HandshakeReply::Accepted { features }) => {
+                info!("the server accepted our handshake, using {features:?}.");
+                *handshake = Handshake::Accepted { features };
+            }
+            ServerSystemMessage::Handshake(HandshakeReply::Rejected(rejection)) => {
+                events
+                    .mismatch_event
+                    .send(ProtocolMismatch(ProtocolError::Rejected(rejection)));
+                return;
+            }
+            ServerSystemMessage::ListRooms(rooms) => {
+                for info in rooms {
+                    commands.spawn((RoomKey(info.id), info));
                 }
             }
-            ServerSystemMessage::Error(message) => {
-                error_event.send(NewError(message));
+            ServerSystemMessage::Error(error) => {
+                events.error_event.send(NewError(error));
             }
-            ServerSystemMessage::JoinedRoom(room_id) => {
-                room_change_event.send(RoomChange::Enter(room_id));
+            ServerSystemMessage::JoinedRoom(room_id, config) => {
+                events
+                    .room_change_event
+                    .send(RoomChange::Enter(room_id, config));
             }
             ServerSystemMessage::LeftRoom(room_id) => {
-                room_change_event.send(RoomChange::Exit(room_id));
+                events.room_change_event.send(RoomChange::Exit(room_id));
+            }
+            ServerSystemMessage::ClockPong { sent, server_time } => {
+                server_clock.record(sent, server_time);
             }
         }
     }
@@ -0,0 +1,30 @@
+use crate::client::components::{
+    pending_requests::PendingRequests,
+    system_message::{SystemMessage, SystemMessageType},
+};
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use std::time::{Duration, Instant};
+
+/// sends requests the server hasn't answered in time again, and lets the player know when it
+/// gives up on one.
+pub fn retry_requests(
+    mut commands: Commands,
+    mut client: ResMut<RenetClient>,
+    mut requests: ResMut<PendingRequests>,
+) {
+    let given_up = requests.retry(&mut client, Instant::now());
+
+    for message in &given_up {
+        warn!("the server never answered {message:?}.");
+    }
+
+    if !given_up.is_empty() {
+        commands.spawn(SystemMessage {
+            display_duration: Duration::from_secs_f32(2.5),
+            message: "the server isn't answering.".into(),
+            msg_type: SystemMessageType::MiscError,
+            shown: None,
+        });
+    }
+}
@@ -0,0 +1,31 @@
+use crate::client::components::handshake::Handshake;
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use real_time_chess::{ClientChannel, FEATURES, PROTOCOL_VERSION, SystemRequest};
+use std::time::{Duration, Instant};
+
+/// how long to wait for an answer before saying hello again. the server's answer isn't
+/// guaranteed to arrive.
+const HELLO_RESEND: Duration = Duration::from_secs(1);
+
+/// tells the server which version of the protocol we speak and what we support, until it
+/// answers. the hello isn't a tracked request, since it has to be readable by servers that
+/// don't know about request ids.
+pub fn send_hello(
+    mut client: ResMut<RenetClient>,
+    handshake: Res<Handshake>,
+    mut last_hello: Local<Option<Instant>>,
+) {
+    if *handshake != Handshake::Pending
+        || last_hello.is_some_and(|last| last.elapsed() < HELLO_RESEND)
+    {
+        return;
+    }
+
+    *last_hello = Some(Instant::now());
+    let hello = SystemRequest::Hello {
+        version: PROTOCOL_VERSION,
+        features: FEATURES.to_vec(),
+    };
+    client.send_message(ClientChannel::System, bincode::serialize(&hello).unwrap());
+}
@@ -0,0 +1,33 @@
+use crate::client::components::{pending_requests::PendingRequests, server_clock::ServerClock};
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use real_time_chess::ClientSystemMessage;
+use std::time::Duration;
+
+/// pings quickly until there are a few samples to go on, then just often enough to follow drift.
+const WARMUP_PINGS: usize = 4;
+const WARMUP_INTERVAL: Duration = Duration::from_millis(250);
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// pings the server so `ServerClock` can work out how far our clock is from its. pings aren't
+/// waited on, a late pong is just a worse sample and the next ping is never far off.
+pub fn sync_server_clock(
+    mut client: ResMut<RenetClient>,
+    mut requests: ResMut<PendingRequests>,
+    clock: Res<ServerClock>,
+    mut last_ping: Local<Option<Duration>>,
+) {
+    let now = clock.local_time();
+    let interval = if clock.samples() < WARMUP_PINGS {
+        WARMUP_INTERVAL
+    } else {
+        PING_INTERVAL
+    };
+
+    if last_ping.is_some_and(|last| now.saturating_sub(last) < interval) {
+        return;
+    }
+
+    *last_ping = Some(now);
+    requests.send_untracked(&mut client, ClientSystemMessage::ClockPing { sent: now });
+}
@@ -1,8 +1,21 @@
-use crate::client::components::marker_components::GameCamera;
+use crate::client::components::{
+    board_piece::BoardPiece, marker_components::GameCamera, queued_premoves::QueuedPremoves,
+};
 use bevy::prelude::*;
 
-pub fn teardown_game(mut commands: Commands, game_cam: Query<Entity, With<GameCamera>>) {
+pub fn teardown_game(
+    mut commands: Commands,
+    game_cam: Query<Entity, With<GameCamera>>,
+    pieces: Query<Entity, With<BoardPiece>>,
+    mut premoves: ResMut<QueuedPremoves>,
+) {
+    premoves.0.clear();
+
     for cam in game_cam.iter() {
         commands.entity(cam).despawn();
     }
+
+    for piece in pieces.iter() {
+        commands.entity(piece).despawn_recursive();
+    }
 }
@@ -1,445 +1,680 @@
 #![feature(let_chains)]
-use anyhow::{Result, ensure};
-use bevy::{
-    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
-    prelude::*,
-};
-use bevy_egui::EguiPlugin;
+#[cfg(feature = "dashboard")]
+use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
+use bevy::{app::ScheduleRunnerPlugin, log::LogPlugin, prelude::*};
+#[cfg(feature = "dashboard")]
+use bevy_egui::{EguiContexts, EguiPlugin};
 use bevy_renet::{
     RenetServerPlugin,
     renet::{ClientId, RenetServer, ServerEvent},
 };
 use real_time_chess::{
-    ChessPiece, ClientChannel, ClientInGameMessage, ClientSystemMessage, File, Location,
-    PROTOCOL_ID, Player, PlayerColor, Rank, RoomID, ServerChannel, ServerInGameMessage,
-    ServerSystemMessage, Slope, connection_config,
+    ChessPiece, ClientChannel, ClientInGameMessage, ClientSystemMessage, Cooldown, DecodeError,
+    FEATURES, Feature, FlightInfo, HandshakeRejection, HandshakeReply, Location, MoveRejection,
+    PROTOCOL_ID, PROTOCOL_VERSION, PlacedPiece, Player, PlayerColor, Premove, RequestId,
+    RoomConfig, RoomID, RoomInfo, SeatChoice, ServerChannel, ServerInGameMessage,
+    ServerSystemMessage, ServerTime, SpectatorPolicy, SystemError, SystemRequest, SystemResponse,
+    TimeControl, Variant, Visibility, connection_config, decode, display_room_id,
+    rules::{
+        Board, Clock, GameState, MoveError, MoveQueue, PremoveQueue, QueuedMove, Resolution,
+        SystemClock, resolve_batch,
+    },
+    settings::{
+        SettingsError, load_settings,
+        server::{ServerSettings, USAGE},
+    },
 };
+#[cfg(feature = "dashboard")]
 use renet_visualizer::RenetServerVisualizer;
+use serde::Serialize;
 use std::{
     collections::HashMap,
-    ops::{Index, IndexMut},
+    hash::{BuildHasher, Hasher, RandomState},
     time::{Duration, Instant},
 };
 
 pub mod server;
 
-pub type BoardPiece = (ChessPiece, PlayerColor, Instant, Duration);
-pub type BoardSquare = Option<BoardPiece>;
+/// where a room is in its lifecycle.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RoomState {
+    #[default]
+    Playing,
+    /// a king was captured. the room stays around so its members can see the result but no more
+    /// moves are accepted.
+    Finished { winner: PlayerColor },
+    /// both kings were captured at once. treated like `Finished`.
+    Drawn,
+}
 
-#[derive(Debug, Clone)]
-pub struct Board {
-    squares: [[BoardSquare; 8]; 8],
+#[derive(Debug, Clone, Component)]
+pub struct Room {
+    id: RoomID,
+    game: GameState,
+    /// the rules the room was started with.
+    config: RoomConfig,
+    /// when both seats were filled and the clock started for the time limit.
+    started: Option<Instant>,
+    /// when the server's clock started. times sent to clients are counted from here.
+    epoch: Instant,
+    state: RoomState,
+    /// the client that started the room.
+    host: Option<ClientId>,
+    /// the client that joined to play against the host.
+    player2: Option<ClientId>,
+    /// the color the host plays as. `player2` plays the other one.
+    host_color: PlayerColor,
+    /// clients watching the game without playing.
+    observers: Vec<ClientId>,
+    /// seated players that asked for a rematch since the game finished.
+    rematch: Vec<ClientId>,
+    /// the number of moves made in the room, sent along with every move so clients can tell
+    /// when they missed one.
+    seq: u64,
+    /// moves waiting to be resolved as a batch.
+    queue: MoveQueue<ClientId>,
+    /// moves waiting for their piece to come off cooldown.
+    premoves: PremoveQueue<ClientId>,
+    /// the last time the room looked for pieces coming off cooldown.
+    ready_checked: Instant,
 }
 
-impl Default for Board {
-    fn default() -> Self {
-        let mut room = Board {
-            squares: [[None; 8]; 8],
+impl Room {
+    pub fn new(
+        id: RoomID,
+        host: ClientId,
+        seat: SeatChoice,
+        config: RoomConfig,
+        input_window: Duration,
+        clock: &GameClock,
+    ) -> Self {
+        let host_color = match seat {
+            SeatChoice::Color(color) => color,
+            SeatChoice::Random => random_color(),
         };
-        let white_pieces: Vec<(Location, ChessPiece)> = vec![
-            ((Rank::A, File::One), ChessPiece::R),
-            ((Rank::B, File::One), ChessPiece::N),
-            ((Rank::C, File::One), ChessPiece::B),
-            ((Rank::D, File::One), ChessPiece::Q),
-            ((Rank::E, File::One), ChessPiece::K),
-            ((Rank::F, File::One), ChessPiece::B),
-            ((Rank::G, File::One), ChessPiece::N),
-            ((Rank::H, File::One), ChessPiece::R),
-            ((Rank::A, File::Two), ChessPiece::Pawn),
-            ((Rank::B, File::Two), ChessPiece::Pawn),
-            ((Rank::C, File::Two), ChessPiece::Pawn),
-            ((Rank::D, File::Two), ChessPiece::Pawn),
-            ((Rank::E, File::Two), ChessPiece::Pawn),
-            ((Rank::F, File::Two), ChessPiece::Pawn),
-            ((Rank::G, File::Two), ChessPiece::Pawn),
-            ((Rank::H, File::Two), ChessPiece::Pawn),
-        ];
-        let black_pieces: Vec<(Location, ChessPiece)> = vec![
-            ((Rank::A, File::Eight), ChessPiece::R),
-            ((Rank::B, File::Eight), ChessPiece::N),
-            ((Rank::C, File::Eight), ChessPiece::B),
-            ((Rank::D, File::Eight), ChessPiece::Q),
-            ((Rank::E, File::Eight), ChessPiece::K),
-            ((Rank::F, File::Eight), ChessPiece::B),
-            ((Rank::G, File::Eight), ChessPiece::N),
-            ((Rank::H, File::Eight), ChessPiece::R),
-            ((Rank::A, File::Seven), ChessPiece::Pawn),
-            ((Rank::B, File::Seven), ChessPiece::Pawn),
-            ((Rank::C, File::Seven), ChessPiece::Pawn),
-            ((Rank::D, File::Seven), ChessPiece::Pawn),
-            ((Rank::E, File::Seven), ChessPiece::Pawn),
-            ((Rank::F, File::Seven), ChessPiece::Pawn),
-            ((Rank::G, File::Seven), ChessPiece::Pawn),
-            ((Rank::H, File::Seven), ChessPiece::Pawn),
-        ];
-
-        let inst = Instant::now();
-        let dur = Duration::from_secs_f32(0.0);
-
-        for (loc, piece) in white_pieces {
-            room[&loc] = Some((piece, PlayerColor::White, inst, dur));
-        }
-
-        for (loc, piece) in black_pieces {
-            room[&loc] = Some((piece, PlayerColor::Black, inst, dur));
-        }
-
-        room
+
+        Self {
+            id,
+            game: new_game(&config, clock.now()),
+            config,
+            started: None,
+            epoch: clock.epoch,
+            state: RoomState::Playing,
+            host: Some(host),
+            player2: None,
+            host_color,
+            observers: Vec::new(),
+            rematch: Vec::new(),
+            seq: 0,
+            queue: MoveQueue::new(input_window),
+            premoves: PremoveQueue::default(),
+            ready_checked: clock.now(),
+        }
     }
-}
 
-impl Board {
-    pub fn get_coords(&self) -> Vec<((usize, usize), BoardPiece)> {
-        let all_squares = self.squares.iter().enumerate().map(move |(rank, files)| {
-            files
-                .iter()
-                .enumerate()
-                .map(move |(file, squares)| squares.map(|square| ((rank, file), square)))
-        });
-        all_squares.flatten().filter_map(|square| square).collect()
+    /// the color `client_id` plays as, or `None` if they don't have a seat in this room.
+    pub fn color_of(&self, client_id: ClientId) -> Option<PlayerColor> {
+        if self.host == Some(client_id) {
+            Some(self.host_color)
+        } else if self.player2 == Some(client_id) {
+            Some(self.host_color.opponent())
+        } else {
+            None
+        }
     }
-}
 
-impl Index<&Location> for Board {
-    type Output = Option<(ChessPiece, PlayerColor, Instant, Duration)>;
+    /// the seated players and the colors they play as.
+    pub fn seats(&self) -> impl Iterator<Item = (ClientId, PlayerColor)> + '_ {
+        self.host
+            .map(|host| (host, self.host_color))
+            .into_iter()
+            .chain(
+                self.player2
+                    .map(|player| (player, self.host_color.opponent())),
+            )
+    }
+
+    /// records that `client_id` wants a rematch. once both players have asked the board is
+    /// reset and they swap colors. returns true when that happens.
+    pub fn request_rematch(&mut self, client_id: ClientId, now: Instant) -> bool {
+        if self.state == RoomState::Playing || self.color_of(client_id).is_none() {
+            return false;
+        }
 
-    fn index(&self, index: &Location) -> &Self::Output {
-        let rank: usize = index.0.into();
-        let f: usize = index.1.into();
-        &self.squares[rank][f]
+        if !self.rematch.contains(&client_id) {
+            self.rematch.push(client_id);
+        }
+
+        if self.seats().count() < 2
+            || self
+                .seats()
+                .any(|(player, _)| !self.rematch.contains(&player))
+        {
+            return false;
+        }
+
+        self.game = new_game(&self.config, now);
+        self.started = Some(now);
+        self.ready_checked = now;
+        self.state = RoomState::Playing;
+        self.host_color = self.host_color.opponent();
+        self.rematch.clear();
+        self.queue.clear();
+        self.premoves.clear();
+
+        true
     }
-}
 
-impl IndexMut<&Location> for Board {
-    fn index_mut(&mut self, index: &Location) -> &mut Self::Output {
-        let rank: usize = index.0.into();
-        let f: usize = index.1.into();
-        &mut self.squares[rank][f]
+    /// everyone in the room, players first.
+    pub fn members(&self) -> impl Iterator<Item = ClientId> + '_ {
+        self.host
+            .into_iter()
+            .chain(self.player2)
+            .chain(self.observers.iter().copied())
     }
-}
 
-#[derive(Debug, Default, Clone, Component)]
-pub struct Room {
-    id: RoomID,
-    board: Board,
-    // TODO: add a Host and player2 field
-    // TODO: add a spectators field
-}
+    pub fn has_free_seat(&self) -> bool {
+        self.host.is_none() || self.player2.is_none()
+    }
 
-impl Room {
-    pub fn make_move_for(
-        &mut self,
-        player: &mut Player,
-        from: Location,
-        to: Location,
-    ) -> ServerInGameMessage {
-        let Some((piece, moving_peice_color, last_moved, cooldown)) = self.board[&from].clone()
-        else {
-            error!("{}, tried to move a nonexisting peice.", player.id);
-            return ServerInGameMessage::InvalidMove(format!(
-                "there is no peice to move at possision {from:?}"
-            ));
-        };
+    /// seats a client in the room, returning the color they were given or `None` if both seats
+    /// are already taken.
+    pub fn join(&mut self, client_id: ClientId, now: Instant) -> Option<PlayerColor> {
+        if self.host.is_none() {
+            self.host = Some(client_id);
+        } else if self.player2.is_none() {
+            self.player2 = Some(client_id);
+        } else {
+            return None;
+        }
 
-        if !self.moving_own_peice(player.color, moving_peice_color) {
-            return ServerInGameMessage::InvalidMove(format!("you can only move your own peices."));
+        if self.seats().count() == 2 {
+            self.started.get_or_insert(now);
         }
 
-        if self.self_capture(player.color, &to) {
-            return ServerInGameMessage::InvalidMove(format!(
-                "you can't move a peice to a square ocupied by one of your own peice."
-            ));
+        self.color_of(client_id)
+    }
+
+    /// adds a client to the room as an observer.
+    pub fn watch(&mut self, client_id: ClientId) {
+        if !self.observers.contains(&client_id) {
+            self.observers.push(client_id);
         }
+    }
 
-        // calculate a vector of movement for the peice and see if its valid.
-        if let Err(e) = self.validated_move_vec(piece, &from, &to, moving_peice_color) {
-            return ServerInGameMessage::InvalidMove(format!("{e}"));
+    /// how the room shows up in the room listing.
+    pub fn info(&self) -> RoomInfo {
+        RoomInfo {
+            id: self.id,
+            players: self.seats().count() as u8,
+            spectators: self.observers.len(),
+            config: self.config,
         }
+    }
 
-        if self.durring_cooldown(last_moved, cooldown) {
-            player.cooldown += cooldown / 3;
+    /// takes `client_id` out of the room. a player leaving a game that's still being played
+    /// forfeits it, their opponent is told they left and observers see the opponent win.
+    pub fn leave(&mut self, server: &mut RenetServer, client_id: ClientId) {
+        let forfeit = self
+            .color_of(client_id)
+            .filter(|_| self.state == RoomState::Playing && self.seats().count() == 2);
 
-            return ServerInGameMessage::InvalidMove(format!(
-                "peice at possision {from:?} is on cooldown."
-            ));
-        } else if self.penalty_move(last_moved, cooldown) {
-            player.cooldown += cooldown / 4;
+        if self.host == Some(client_id) {
+            self.host = None;
         }
 
-        // check for capture.
-        let capture = self.capture(player.color, &to);
+        if self.player2 == Some(client_id) {
+            self.player2 = None;
+        }
 
-        // move peice
-        self.make_move(&from, &to, player.cooldown);
+        self.observers.retain(|observer| *observer != client_id);
+        self.rematch.retain(|player| *player != client_id);
+        self.premoves.drop_sender(&client_id);
 
-        ServerInGameMessage::MoveRecv {
-            player: player.color,
-            from,
-            to,
-            capture,
-            cooldown: player.cooldown,
+        if let Some(color) = forfeit {
+            let winner = color.opponent();
+            info!(
+                "{color:?} left room {}, {winner:?} wins.",
+                display_room_id(&self.id)
+            );
+            self.state = RoomState::Finished { winner };
+
+            let disconnect = bincode::serialize(&ServerInGameMessage::OpponentDisconect).unwrap();
+            let victory = bincode::serialize(&ServerInGameMessage::Victory(winner)).unwrap();
+            for member in self.members() {
+                let message = match self.color_of(member) {
+                    Some(_) => disconnect.clone(),
+                    None => victory.clone(),
+                };
+                server.send_message(member, ServerChannel::InGame, message);
+            }
         }
     }
 
-    fn validated_move_vec(
-        &mut self,
-        piece: ChessPiece,
-        from: &Location,
-        to: &Location,
-        moving_peice_color: PlayerColor,
-    ) -> Result<()> {
-        let move_vector = self.calc_move_vec(&from, &to);
-        let (magnitude, slope) = move_vector;
-
-        if piece != ChessPiece::N {
-            self.piece_in_way(magnitude, slope, from, moving_peice_color)?;
-        }
-
-        let angle = slope.to_degrees();
-
-        match piece {
-            ChessPiece::K => self.validate_king_move(magnitude, angle),
-            ChessPiece::N => self.validate_knight_move(magnitude, angle),
-            ChessPiece::Q => self.validate_queen_move(angle),
-            ChessPiece::B => self.validate_bishop_move(angle),
-            ChessPiece::R => self.validate_rook_move(angle),
-            ChessPiece::Pawn => {
-                self.validate_pawn_move(magnitude, angle, from, to, moving_peice_color)
-            }
-        }?;
+    /// sends `message` to every member of the room.
+    pub fn broadcast<T: Serialize>(
+        &self,
+        server: &mut RenetServer,
+        channel: ServerChannel,
+        message: &T,
+    ) {
+        let channel: u8 = channel.into();
+        let Ok(message) = bincode::serialize(message) else {
+            error!(
+                "could not serialize a message for room {}.",
+                display_room_id(&self.id)
+            );
+            return;
+        };
 
-        Ok(())
+        for member in self.members() {
+            server.send_message(member, channel, message.clone());
+        }
     }
 
-    fn piece_in_way(
-        &mut self,
-        magnitude: f32,
-        angle: Slope,
-        from: &Location,
-        // to: &Location,
-        moving_peice_color: PlayerColor,
-    ) -> Result<()> {
-        let make_key = |angle: Slope| format!("({:.2}/{:.2})", angle.rise, angle.run);
-
-        let piece_vecs: HashMap<String, (f32, PlayerColor)> = self
-            .board
-            .get_coords()
-            .into_iter()
-            .map(|((rank, file), (_, color, _, _))| {
-                let (mag, angle) = self.do_calc_move_vec(
-                    (from.0 as usize) as f32,
-                    rank as f32,
-                    (from.1 as usize) as f32,
-                    file as f32,
-                );
-                (make_key(angle), (mag, color))
-            })
-            .collect();
+    /// `at` on the server's clock, to send to clients.
+    fn timestamp(&self, at: Instant) -> ServerTime {
+        ServerTime::since(self.epoch, at)
+    }
 
-        let same_vec = piece_vecs.get(&make_key(angle));
+    /// the whole board as it stands at `now`.
+    pub fn snapshot(&self, now: Instant) -> ServerInGameMessage {
+        ServerInGameMessage::BoardSnapshot {
+            seq: self.seq,
+            pieces: self.game.board.placed_pieces(),
+            cooldowns: self
+                .game
+                .cooldowns(now)
+                .into_iter()
+                .map(|(pos, ready_at)| Cooldown {
+                    pos,
+                    ready_at: self.timestamp(ready_at),
+                })
+                .collect(),
+            flights: self
+                .game
+                .flights()
+                .iter()
+                .map(|flight| FlightInfo {
+                    piece: PlacedPiece {
+                        pos: flight.from,
+                        piece: flight.piece.piece,
+                        color: flight.player,
+                    },
+                    to: flight.to,
+                    departed: self.timestamp(flight.departed),
+                    arrives_at: self.timestamp(flight.arrives),
+                })
+                .collect(),
+        }
+    }
 
-        ensure!(
-            same_vec.is_some_and(|(mag, color)| {
-                (moving_peice_color == *color && *mag < magnitude)
-                    || (moving_peice_color != *color && *mag <= magnitude)
-            }) || same_vec.is_none(),
-            "there was a piece in the way of that movement"
-        );
+    /// the color `client_id` moves pieces for, or the rejection to send back if they aren't
+    /// allowed to move at all.
+    fn mover(&self, client_id: ClientId) -> Result<PlayerColor, ServerInGameMessage> {
+        let Some(player) = self.color_of(client_id) else {
+            if self.observers.contains(&client_id) {
+                return Err(ServerInGameMessage::InvalidMove(MoveRejection::Spectating));
+            }
 
-        Ok(())
+            return Err(ServerInGameMessage::InvalidMove(MoveRejection::NotSeated));
+        };
+
+        match self.state {
+            RoomState::Playing => Ok(player),
+            RoomState::Finished { winner } => Err(ServerInGameMessage::InvalidMove(
+                MoveRejection::Rules(MoveError::GameOver { winner }),
+            )),
+            RoomState::Drawn => Err(ServerInGameMessage::InvalidMove(MoveRejection::Rules(
+                MoveError::GameDrawn,
+            ))),
+        }
     }
 
-    fn validate_king_move(
+    /// queues a move from `client_id` to be resolved with the rest of its batch. returns the
+    /// rejection to send back if they aren't allowed to move at all.
+    pub fn queue_move(
         &mut self,
-        magnitude: f32,
-        angle: f32,
-        // from: &Location,
-        // to: &Location,
-    ) -> Result<()> {
-        ensure!(
-            ((magnitude == 1.414 && vec![45., 135., 225., 315.].contains(&angle))
-                || (magnitude == 1. && vec![0.0, 90., 180., 270.].contains(&angle))),
-        );
+        client_id: ClientId,
+        from: Location,
+        to: Location,
+        promotion: Option<ChessPiece>,
+        now: Instant,
+    ) -> Option<ServerInGameMessage> {
+        let player = match self.mover(client_id) {
+            Ok(player) => player,
+            Err(rejection) => return Some(rejection),
+        };
+
+        self.queue.push(QueuedMove {
+            sender: client_id,
+            player,
+            from,
+            to,
+            promotion,
+            received: now,
+        });
 
-        Ok(())
+        None
     }
 
-    fn validate_knight_move(
+    /// queues a premove from `client_id` to be made once its piece is off cooldown. returns the
+    /// rejection to send back if the move isn't legal in the first place.
+    pub fn queue_premove(
         &mut self,
-        magnitude: f32,
-        angle: f32,
-        // from: &Location,
-        // to: &Location,
-    ) -> Result<()> {
-        ensure!(
-            vec![1. / 2., 2. / 1.].contains(&angle.abs()),
-            "Knight movement had wrong angle."
-        );
+        server: &mut RenetServer,
+        client_id: ClientId,
+        premove: Premove,
+        now: Instant,
+    ) -> Option<ServerInGameMessage> {
+        let player = match self.mover(client_id) {
+            Ok(player) => player,
+            Err(rejection) => return Some(rejection),
+        };
 
-        // check magnitude is 2.23...
-        let mag = (magnitude * 100.).round() / 100.0;
-        ensure!(mag == 2.24, "Knight movement had wrong magnitude.");
+        // a piece that is still travelling can be premoved from where it's going to land.
+        let landing = self
+            .game
+            .flights()
+            .iter()
+            .any(|flight| flight.player == player && flight.to == premove.from);
+
+        if !landing
+            && let Err(e) = self
+                .game
+                .check_move(player, &premove.from, &premove.to, now)
+        {
+            return Some(ServerInGameMessage::InvalidMove(MoveRejection::Rules(e)));
+        }
 
-        Ok(())
+        self.premoves.queue(QueuedMove {
+            sender: client_id,
+            player,
+            from: premove.from,
+            to: premove.to,
+            promotion: premove.promotion,
+            received: now,
+        });
+        self.send_premoves(server, client_id);
+
+        None
     }
 
-    fn validate_queen_move(
+    /// cancels the premove `client_id` queued for the piece on `from`.
+    pub fn cancel_premove(
         &mut self,
-        // magnitude: f32,
-        angle: f32,
-        // from: &Location,
-        // to: &Location,
-    ) -> Result<()> {
-        ensure!(
-            vec![45., 135., 225., 315., 0.0, 90., 180., 270.].contains(&angle),
-            "the queen must move diaganoly, vertically, or horzonatally."
-        );
-
-        Ok(())
+        server: &mut RenetServer,
+        client_id: ClientId,
+        from: Location,
+    ) {
+        if let Some(player) = self.color_of(client_id) {
+            self.premoves.cancel(player, &from);
+            self.send_premoves(server, client_id);
+        }
     }
 
-    fn validate_bishop_move(
-        &mut self,
-        // magnitude: f32,
-        angle: f32,
-        // from: &Location,
-        // to: &Location,
-    ) -> Result<()> {
-        ensure!(
-            vec![45., 135., 225., 315.].contains(&angle),
-            "the bishop can only move diagonally."
-        );
+    /// sends `client_id` the premoves they have queued.
+    pub fn send_premoves(&self, server: &mut RenetServer, client_id: ClientId) {
+        let premoves = self
+            .color_of(client_id)
+            .map(|player| self.premoves.for_player(player))
+            .unwrap_or_default();
+        let message = bincode::serialize(&ServerInGameMessage::Premoves(premoves)).unwrap();
 
-        Ok(())
+        server.send_message(client_id, ServerChannel::InGame, message);
     }
 
-    fn validate_rook_move(
-        &mut self,
-        // magnitude: f32,
-        angle: f32,
-        // from: &Location,
-        // to: &Location,
-    ) -> Result<()> {
-        ensure!(
-            vec![0.0, 90., 180., 270.].contains(&angle),
-            "the rook can only move vertically or horizontally."
-        );
+    /// makes the premoves whose piece is ready straight away, and lets the players know about
+    /// the ones that had to be dropped.
+    pub fn fire_premoves(&mut self, server: &mut RenetServer, now: Instant) {
+        if self.state != RoomState::Playing {
+            return;
+        }
 
-        Ok(())
+        let (mut ready, dropped) = self.premoves.take_ready(&self.game, now);
+        // only whoever holds the seat now gets to move its pieces.
+        ready.retain(|queued| self.color_of(queued.sender) == Some(queued.player));
+        let mut changed: Vec<ClientId> = Vec::new();
+
+        for (queued, e) in dropped {
+            let message = ServerInGameMessage::InvalidMove(MoveRejection::PremoveDropped {
+                from: queued.from,
+                to: queued.to,
+                reason: e,
+            });
+            server.send_message(
+                queued.sender,
+                ServerChannel::InGame,
+                bincode::serialize(&message).unwrap(),
+            );
+            changed.push(queued.sender);
+        }
+
+        changed.extend(ready.iter().map(|queued| queued.sender));
+        changed.sort();
+        changed.dedup();
+
+        for client_id in changed {
+            self.send_premoves(server, client_id);
+        }
+
+        if !ready.is_empty() {
+            // premoves that fire together can still clash, so they're resolved as a batch.
+            self.apply_batch(server, ready, now, true);
+        }
     }
 
-    fn validate_pawn_move(
-        &mut self,
-        magnitude: f32,
-        angle: f32,
-        from: &Location,
-        to: &Location,
-        moving_peice_color: PlayerColor,
-    ) -> Result<()> {
-        // TODO: include en pesant
-        if (2 == from.0 as usize
-            && self.board[from].is_some_and(
-                |(_piece, moving_peice_color, _last_moved, _cooldown)| {
-                    moving_peice_color == PlayerColor::White
-                },
-            )
-            && angle == 90.
-            && magnitude == 2.)
-            || (7 == from.0 as usize
-                && self.board[from].is_some_and(
-                    |(_piece, moving_peice_color, _last_moved, _cooldown)| {
-                        moving_peice_color == PlayerColor::Black
-                    },
-                )
-                && angle == 270.
-                && magnitude == 2.)
-        {
-            return Ok(());
+    /// resolves the queued moves once their window has closed.
+    pub fn resolve_queue(&mut self, server: &mut RenetServer, now: Instant) {
+        let batch: Vec<_> = self
+            .queue
+            .take_batch(now)
+            .into_iter()
+            // they left the room while the move was waiting.
+            .filter(|queued| self.color_of(queued.sender) == Some(queued.player))
+            .collect();
+
+        if !batch.is_empty() {
+            self.apply_batch(server, batch, now, false);
         }
+    }
 
-        ensure!(
-            angle == 45.0 || angle == 135.0 || angle == 90.0,
-            "pawns can only go forward."
-        );
+    /// makes a batch of moves, letting the room know how each one turned out and the sender
+    /// about each one that was rejected. rejected premoves are reported as dropped.
+    fn apply_batch(
+        &mut self,
+        server: &mut RenetServer,
+        batch: Vec<QueuedMove<ClientId>>,
+        now: Instant,
+        premoved: bool,
+    ) {
+        for resolution in resolve_batch(&mut self.game, batch, now) {
+            match resolution {
+                Resolution::Moved(_, outcome) => {
+                    self.seq += 1;
+                    let message = ServerInGameMessage::MoveRecv {
+                        seq: self.seq,
+                        player: outcome.player,
+                        from: outcome.from,
+                        to: outcome.to,
+                        capture: outcome.captured.is_some(),
+                        captured_at: outcome.captured_at,
+                        promotion: outcome.promotion,
+                        rook_move: outcome.castle,
+                        cooldown: outcome.cooldown,
+                        ready_at: self.timestamp(outcome.ready_at),
+                        arrives_at: outcome.arrives.map(|arrives| self.timestamp(arrives)),
+                    };
+                    self.broadcast(server, ServerChannel::InGame, &message);
+                }
+                Resolution::Rejected(queued, e) => {
+                    if let MoveError::NoPieceAtSource(_) = e {
+                        error!("{}, tried to move a nonexisting peice.", queued.sender);
+                    }
 
-        if angle == 45. || angle == 135. {
-            ensure!(
-                self.board[to].is_some_and(|(_, color, _, _)| moving_peice_color != color)
-                    && ((magnitude * 100.).round() / 100.) == 1.41,
-                "pawns can only move diaganoly when capturing a piece."
-            );
-        } else if angle == 90. {
-            ensure!(
-                self.board[to].is_none() && magnitude == 1.,
-                "pawns cannot capture forwards."
-            );
+                    let rejection = if premoved {
+                        MoveRejection::PremoveDropped {
+                            from: queued.from,
+                            to: queued.to,
+                            reason: e,
+                        }
+                    } else {
+                        MoveRejection::Rules(e)
+                    };
+                    let message = ServerInGameMessage::InvalidMove(rejection);
+                    server.send_message(
+                        queued.sender,
+                        ServerChannel::InGame,
+                        bincode::serialize(&message).unwrap(),
+                    );
+                }
+                Resolution::Bounced { to, moves } => {
+                    let message = ServerInGameMessage::Bounced {
+                        to,
+                        from: moves.iter().map(|queued| queued.from).collect(),
+                    };
+                    self.broadcast(server, ServerChannel::InGame, &message);
+                }
+                Resolution::Traded(a, b) => {
+                    self.seq += 1;
+                    let message = ServerInGameMessage::Traded {
+                        seq: self.seq,
+                        squares: (a.from, b.from),
+                    };
+                    self.broadcast(server, ServerChannel::InGame, &message);
+                }
+            }
         }
 
-        Ok(())
+        self.check_game_end(server);
     }
 
-    pub fn do_calc_move_vec(&mut self, x1: f32, x2: f32, y1: f32, y2: f32) -> (f32, Slope) {
-        let magnitude = ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt();
-        let angle = Slope {
-            rise: (y1 - y2),
-            run: (x1 - x2),
-        };
+    /// lets the room know about the pieces that came off cooldown since the last check.
+    pub fn notify_ready(&mut self, server: &mut RenetServer, now: Instant) {
+        let since = std::mem::replace(&mut self.ready_checked, now);
 
-        (magnitude, angle)
+        if self.state != RoomState::Playing {
+            return;
+        }
+
+        for (square, stage) in self.game.readied(since, now) {
+            let message = ServerInGameMessage::PieceReady { square, stage };
+            self.broadcast(server, ServerChannel::InGame, &message);
+        }
     }
 
-    pub fn calc_move_vec(&mut self, from: &Location, to: &Location) -> (f32, Slope) {
-        let x1 = (from.0 as usize) as f32;
-        let x2 = (to.0 as usize) as f32;
+    /// lands the travelling pieces that have arrived and lets the room know where they landed.
+    pub fn land_flights(&mut self, server: &mut RenetServer, now: Instant) {
+        let landings = self.game.land_flights(now);
 
-        let y1 = (from.1 as usize) as f32;
-        let y2 = (to.1 as usize) as f32;
+        if landings.is_empty() {
+            return;
+        }
 
-        self.do_calc_move_vec(x1, x2, y1, y2)
+        for landing in landings {
+            self.seq += 1;
+            let message = ServerInGameMessage::Arrived {
+                seq: self.seq,
+                player: landing.flight.player,
+                from: landing.flight.from,
+                to: landing.flight.to,
+                captured_at: landing.captured_at,
+                promotion: landing.flight.promotion,
+                rook_move: landing.castle,
+                intercepted: landing
+                    .intercepted
+                    .iter()
+                    .map(|flight| flight.from)
+                    .collect(),
+                ready_at: self.timestamp(landing.flight.ready_at()),
+            };
+            self.broadcast(server, ServerChannel::InGame, &message);
+        }
+
+        self.check_game_end(server);
     }
 
-    pub fn make_move(&mut self, from: &Location, to: &Location, cooldown: Duration) {
-        let piece =
-            self.board[&from]
-                .clone()
-                .map(|(piece, moving_peice_color, _last_moved, _cooldown)| {
-                    (
-                        piece,
-                        moving_peice_color,
-                        Instant::now() + Duration::from_secs_f32(0.1),
-                        cooldown,
-                    )
-                });
-        self.board[&to] = piece;
-        self.board[&from] = None;
+    /// ends the game in a draw if it has a time limit that ran out.
+    fn check_time_limit(&mut self, server: &mut RenetServer, now: Instant) {
+        let TimeControl::Limit(limit) = self.config.time_control else {
+            return;
+        };
+
+        if self
+            .started
+            .is_some_and(|started| now.saturating_duration_since(started) >= limit)
+        {
+            self.game.declare_draw();
+            self.check_game_end(server);
+        }
     }
 
-    pub fn penalty_move(&mut self, last_moved: Instant, cooldown: Duration) -> bool {
-        last_moved.elapsed() > cooldown / 4 * 3 && last_moved.elapsed() < cooldown
+    /// finishes the game and lets the room know if a king was captured.
+    fn check_game_end(&mut self, server: &mut RenetServer) {
+        if self.state != RoomState::Playing {
+            return;
+        }
+
+        if let Some(winner) = self.game.winner() {
+            info!("{winner:?} won in room {}.", display_room_id(&self.id));
+            self.state = RoomState::Finished { winner };
+            self.broadcast(
+                server,
+                ServerChannel::InGame,
+                &ServerInGameMessage::Victory(winner),
+            );
+        } else if self.game.is_drawn() {
+            info!("room {} ended in a draw.", display_room_id(&self.id));
+            self.state = RoomState::Drawn;
+            self.broadcast(server, ServerChannel::InGame, &ServerInGameMessage::Draw);
+        }
     }
+}
 
-    pub fn durring_cooldown(&mut self, last_moved: Instant, cooldown: Duration) -> bool {
-        last_moved.elapsed() < cooldown / 4 * 3
+/// a fresh game played by the given rules.
+fn new_game(config: &RoomConfig, now: Instant) -> GameState {
+    let mut game = GameState::with_policy(Board::starting(now), config.cooldown.policy());
+    game.travel_time = match config.variant {
+        Variant::Classic => None,
+        Variant::Travel { per_square } => Some(per_square),
+    };
+
+    game
+}
+
+/// picks white or black with even odds.
+fn random_color() -> PlayerColor {
+    if RandomState::new().build_hasher().finish().is_multiple_of(2) {
+        PlayerColor::White
+    } else {
+        PlayerColor::Black
     }
+}
 
-    pub fn moving_own_peice(
-        &mut self,
-        player_color: PlayerColor,
-        moving_peice_color: PlayerColor,
-    ) -> bool {
-        moving_peice_color == player_color
+/// the clock the rooms read the time from. always the real time outside of tests.
+#[derive(Resource)]
+pub struct GameClock {
+    clock: Box<dyn Clock>,
+    /// when the server started. the times sent to clients are counted from here.
+    pub epoch: Instant,
+}
+
+impl GameClock {
+    pub fn new(clock: Box<dyn Clock>) -> Self {
+        Self {
+            epoch: clock.now(),
+            clock,
+        }
     }
 
-    pub fn capture(&mut self, player_color: PlayerColor, to: &Location) -> bool {
-        self.board[&to]
-            .clone()
-            .is_some_and(|(_, color, _, _)| color != player_color)
+    pub fn now(&self) -> Instant {
+        self.clock.now()
     }
+}
 
-    pub fn self_capture(&mut self, player_color: PlayerColor, to: &Location) -> bool {
-        self.board[&to]
-            .clone()
-            .is_some_and(|(_, color, _, _)| color == player_color)
+impl Default for GameClock {
+    fn default() -> Self {
+        Self::new(Box::new(SystemClock))
     }
 }
 
@@ -447,9 +682,82 @@ impl Room {
 pub struct ServerLobby {
     pub players: HashMap<ClientId, Player>,
     pub room_mem: HashMap<ClientId, RoomID>,
+    /// how many messages each client sent that couldn't be decoded.
+    pub violations: HashMap<ClientId, u32>,
+    /// the features each client that finished its handshake gets to use.
+    pub features: HashMap<ClientId, Vec<Feature>>,
+    /// the rules rooms are played by when whoever starts them doesn't pick any.
+    pub default_room: RoomConfig,
+    /// how long every room holds incoming moves before resolving them.
+    pub input_window: Duration,
+}
+
+/// the most messages a client can send that can't be decoded before it's disconnected.
+const MAX_PROTOCOL_VIOLATIONS: u32 = 5;
+
+impl ServerLobby {
+    /// answers a client's `Hello`. clients on another version of the protocol are turned away,
+    /// the rest get the features both sides support.
+    fn handshake(
+        &mut self,
+        client_id: ClientId,
+        version: u32,
+        features: Vec<Feature>,
+    ) -> HandshakeReply {
+        if version != PROTOCOL_VERSION {
+            info!("{client_id} speaks protocol version {version}, turning it away.");
+            self.features.remove(&client_id);
+
+            return HandshakeReply::Rejected(HandshakeRejection::UnsupportedVersion {
+                client: version,
+                server: PROTOCOL_VERSION,
+            });
+        }
+
+        let features: Vec<Feature> = features
+            .into_iter()
+            .filter(|feature| FEATURES.contains(feature))
+            .collect();
+        info!("{client_id} shook hands, using {features:?}.");
+        self.features.insert(client_id, features.clone());
+
+        HandshakeReply::Accepted { features }
+    }
+
+    /// the feature `client_id` would need for something, if it didn't say it supports it.
+    fn missing_feature(&self, client_id: ClientId, feature: Option<Feature>) -> Option<Feature> {
+        feature.filter(|feature| {
+            !self
+                .features
+                .get(&client_id)
+                .is_some_and(|features| features.contains(feature))
+        })
+    }
+
+    /// logs a message from `client_id` that couldn't be decoded. returns true if that was one
+    /// too many and the client was disconnected.
+    fn protocol_violation(
+        &mut self,
+        server: &mut RenetServer,
+        client_id: ClientId,
+        e: DecodeError,
+    ) -> bool {
+        let violations = self.violations.entry(client_id).or_default();
+        *violations += 1;
+        warn!("{client_id} sent a message that couldn't be decoded ({violations}): {e}");
+
+        if *violations < MAX_PROTOCOL_VIOLATIONS {
+            return false;
+        }
+
+        warn!("disconnecting {client_id} after {violations} protocol violations.");
+        server.disconnect(client_id);
+
+        true
+    }
 }
 
-fn add_network(app: &mut App) {
+fn add_network(app: &mut App, settings: &ServerSettings) {
     use bevy_renet::netcode::{
         NetcodeServerPlugin, NetcodeServerTransport, ServerAuthentication, ServerConfig,
     };
@@ -460,16 +768,15 @@ fn add_network(app: &mut App) {
 
     let server = RenetServer::new(connection_config());
 
-    let public_addr = "127.0.0.1:5000".parse().unwrap();
-    let socket = UdpSocket::bind(public_addr).unwrap();
+    let socket = UdpSocket::bind(settings.bind_addr()).unwrap();
     let current_time: std::time::Duration = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap();
     let server_config = ServerConfig {
         current_time,
-        max_clients: 64,
+        max_clients: settings.max_clients,
         protocol_id: PROTOCOL_ID,
-        public_addresses: vec![public_addr],
+        public_addresses: settings.public_addrs(),
         authentication: ServerAuthentication::Unsecure,
     };
 
@@ -479,21 +786,50 @@ fn add_network(app: &mut App) {
 }
 
 fn main() {
+    let settings: ServerSettings = match load_settings(std::env::args().skip(1)) {
+        Ok(settings) => settings,
+        Err(SettingsError::Help) => {
+            print!("{USAGE}");
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    let log = LogPlugin {
+        level: settings.log_level.level(),
+        ..default()
+    };
+
     let mut app = App::new();
-    app.add_plugins(DefaultPlugins);
+
+    if settings.dashboard {
+        add_dashboard(&mut app, log);
+    } else {
+        // no window to wait on, so the schedule runner keeps the server ticking instead.
+        app.add_plugins(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(settings.tick())));
+        app.add_plugins(log);
+    }
 
     app.add_plugins(RenetServerPlugin);
-    app.add_plugins(FrameTimeDiagnosticsPlugin);
-    app.add_plugins(LogDiagnosticsPlugin::default());
-    app.add_plugins(EguiPlugin);
 
-    app.insert_resource(ServerLobby::default());
+    app.insert_resource(ServerLobby {
+        default_room: settings.room,
+        input_window: settings.input_window,
+        ..default()
+    });
+    app.init_resource::<GameClock>();
 
-    app.insert_resource(RenetServerVisualizer::<200>::default());
+    info!(
+        "listening on {} as {:?}.",
+        settings.bind_addr(),
+        settings.public_addrs()
+    );
+    add_network(&mut app, &settings);
 
-    add_network(&mut app);
-
-    app.add_systems(Update, server_update_system);
+    app.add_systems(Update, (server_update_system, resolve_move_queues).chain());
 
     // app.add_systems(FixedUpdate, apply_velocity_system);
     // app.add_systems(PostUpdate, projectile_on_removal_system);
@@ -502,105 +838,297 @@ fn main() {
     app.run();
 }
 
+/// opens a window showing the network stats of every client, along with frame times in the log.
+#[cfg(feature = "dashboard")]
+fn add_dashboard(app: &mut App, log: LogPlugin) {
+    app.add_plugins(DefaultPlugins.set(log));
+    app.add_plugins(EguiPlugin);
+    app.add_plugins((FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin::default()));
+    app.insert_resource(RenetServerVisualizer::<200>::default());
+    app.add_systems(Update, draw_dashboard);
+}
+
+#[cfg(not(feature = "dashboard"))]
+fn add_dashboard(_app: &mut App, _log: LogPlugin) {
+    eprintln!("--dashboard needs a server built with `--features dashboard`.");
+    std::process::exit(2);
+}
+
+/// shows the network stats of every client in a window. only runs with `--dashboard`.
+#[cfg(feature = "dashboard")]
+fn draw_dashboard(
+    mut egui_contexts: EguiContexts,
+    mut visualizer: ResMut<RenetServerVisualizer<200>>,
+    server: Res<RenetServer>,
+) {
+    visualizer.update(&server);
+    visualizer.show_window(egui_contexts.ctx_mut());
+}
+
 fn server_update_system(
     mut server_events: EventReader<ServerEvent>,
     mut commands: Commands,
     mut lobby: ResMut<ServerLobby>,
     mut rooms: Query<&mut Room>,
     mut server: ResMut<RenetServer>,
-    mut visualizer: ResMut<RenetServerVisualizer<200>>,
+    #[cfg(feature = "dashboard")] mut visualizer: Option<ResMut<RenetServerVisualizer<200>>>,
+    clock: Res<GameClock>,
 ) {
+    let now = clock.now();
+
     for event in server_events.read() {
         match event {
             ServerEvent::ClientConnected { client_id } => {
                 info!("Player {} connected.", client_id);
-                visualizer.add_client(*client_id);
+                #[cfg(feature = "dashboard")]
+                if let Some(visualizer) = visualizer.as_mut() {
+                    visualizer.add_client(*client_id);
+                }
 
-                lobby.players.insert(*client_id, Player {
-                    id: *client_id,
-                    color: PlayerColor::White,
-                    cooldown: Duration::from_secs(5),
-                });
+                lobby.players.insert(
+                    *client_id,
+                    Player {
+                        id: *client_id,
+                        color: PlayerColor::White,
+                    },
+                );
             }
             ServerEvent::ClientDisconnected { client_id, reason } => {
                 info!("Player {} disconnected: {}", client_id, reason);
-                visualizer.remove_client(*client_id);
-                if lobby.players.remove(client_id).is_some() {
-                    lobby.room_mem.remove(&client_id);
+                #[cfg(feature = "dashboard")]
+                if let Some(visualizer) = visualizer.as_mut() {
+                    visualizer.remove_client(*client_id);
+                }
+                lobby.violations.remove(client_id);
+                lobby.features.remove(client_id);
+                if lobby.players.remove(client_id).is_some()
+                    && let Some(room_id) = lobby.room_mem.remove(&client_id)
+                {
+                    rooms
+                        .iter_mut()
+                        .filter(|room| room.id == room_id)
+                        .for_each(|mut room| room.leave(&mut server, *client_id));
                 }
             }
         }
     }
 
-    for client_id in server.clients_id() {
+    'clients: for client_id in server.clients_id() {
         while let Some(message) = server.receive_message(client_id, ClientChannel::System) {
-            if let Ok(command) = bincode::deserialize::<ClientSystemMessage>(&message) {
-                match command {
-                    ClientSystemMessage::ListRooms => {
-                        let message = ServerSystemMessage::ListRooms(
-                            // lobby.rooms.clone().keys().map(|key| *key).collect(),
-                            rooms.iter().map(|room| room.id.clone()).collect(),
-                        );
-                        let message = bincode::serialize(&message).unwrap();
-                        server.send_message(client_id, ServerChannel::System, message);
+            let (request, command) = match decode::<SystemRequest>(&message) {
+                Ok(SystemRequest::Hello { version, features }) => {
+                    (None, ClientSystemMessage::Hello { version, features })
+                }
+                Ok(SystemRequest::Request { id, message }) => (Some(id), message),
+                Err(e) => {
+                    if lobby.protocol_violation(&mut server, client_id, e) {
+                        continue 'clients;
                     }
-                    // ClientMessage::ChatMessage(_mesg) => {}
-                    ClientSystemMessage::StartRoom(room_key) => {
-                        if lobby.players.get(&client_id).is_some()
-                            && !lobby.room_mem.contains_key(&client_id)
-                        {
-                            // lobby.rooms.insert(room_key, Room::default());
-                            if rooms.iter().position(|room| room.id == room_key).is_none() {
-                                commands.spawn(Room {
-                                    id: room_key,
-                                    board: Board::default(),
-                                });
-                                lobby.room_mem.insert(client_id, room_key);
-                                let msg = ServerSystemMessage::JoinedRoom(room_key);
-                                server.send_message(
-                                    client_id,
-                                    ServerChannel::System,
-                                    bincode::serialize(&msg).unwrap(),
-                                );
+
+                    continue;
+                }
+            };
+
+            if !matches!(command, ClientSystemMessage::Hello { .. })
+                && !lobby.features.contains_key(&client_id)
+            {
+                let msg = ServerSystemMessage::Error(SystemError::NoHandshake);
+                send_system(&mut server, client_id, request, msg);
+                continue;
+            }
+
+            match command {
+                ClientSystemMessage::Hello { version, features } => {
+                    let msg = ServerSystemMessage::Handshake(
+                        lobby.handshake(client_id, version, features),
+                    );
+                    send_system(&mut server, client_id, request, msg);
+                }
+                ClientSystemMessage::ListRooms => {
+                    let message = ServerSystemMessage::ListRooms(
+                        // lobby.rooms.clone().keys().map(|key| *key).collect(),
+                        rooms
+                            .iter()
+                            .filter(|room| room.config.visibility == Visibility::Public)
+                            .map(|room| room.info())
+                            .collect(),
+                    );
+                    send_system(&mut server, client_id, request, message);
+                }
+                // ClientMessage::ChatMessage(_mesg) => {}
+                ClientSystemMessage::StartRoom(room_key, seat, config) => {
+                    let config = config.unwrap_or(lobby.default_room);
+                    if let Err(e) = config.validate() {
+                        let msg = ServerSystemMessage::Error(SystemError::BadConfig(e));
+                        send_system(&mut server, client_id, request, msg);
+                    } else if let Some(feature) =
+                        lobby.missing_feature(client_id, config.variant.feature())
+                    {
+                        let msg = ServerSystemMessage::Error(SystemError::MissingFeature(feature));
+                        send_system(&mut server, client_id, request, msg);
+                    } else if lobby.players.get(&client_id).is_some()
+                        && !lobby.room_mem.contains_key(&client_id)
+                    {
+                        // lobby.rooms.insert(room_key, Room::default());
+                        if rooms.iter().position(|room| room.id == room_key).is_none() {
+                            let room = Room::new(
+                                room_key,
+                                client_id,
+                                seat,
+                                config,
+                                lobby.input_window,
+                                &clock,
+                            );
+                            lobby.room_mem.insert(client_id, room_key);
+                            let msg = ServerSystemMessage::JoinedRoom(room_key, config);
+                            send_system(&mut server, client_id, request, msg);
+                            seat_player(&mut lobby, &mut server, client_id, room.host_color);
+                            commands.spawn(room);
+                        } else {
+                            let msg = ServerSystemMessage::Error(SystemError::RoomTaken);
+                            send_system(&mut server, client_id, request, msg);
+                        }
+                    } else {
+                        let msg = ServerSystemMessage::Error(SystemError::AlreadyInRoom);
+                        send_system(&mut server, client_id, request, msg);
+                    }
+                }
+                ClientSystemMessage::JoinRoom(room_key) => {
+                    // TODO: add room join requesting and the like.
+                    let room_config = rooms
+                        .iter()
+                        .find(|room| room.id == room_key)
+                        .map(|room| room.config);
+                    let room_open = rooms
+                        .iter()
+                        .any(|room| room.id == room_key && room.has_free_seat());
+                    let missing = room_config.and_then(|config| {
+                        lobby.missing_feature(client_id, config.variant.feature())
+                    });
+                    if let Some(config) = room_config.filter(|_| room_open)
+                        && !lobby.room_mem.contains_key(&client_id)
+                        && missing.is_none()
+                    {
+                        lobby.room_mem.remove(&client_id);
+                        lobby.room_mem.insert(client_id, room_key);
+                        let msg = ServerSystemMessage::JoinedRoom(room_key, config);
+                        send_system(&mut server, client_id, request, msg);
+
+                        for mut room in rooms.iter_mut().filter(|room| room.id == room_key) {
+                            if let Some(color) = room.join(client_id, now) {
+                                seat_player(&mut lobby, &mut server, client_id, color);
+                            }
+
+                            if room.has_free_seat() {
+                                let snapshot = bincode::serialize(&room.snapshot(now)).unwrap();
+                                server.send_message(client_id, ServerChannel::InGame, snapshot);
                             } else {
-                                let msg = ServerSystemMessage::Error(
-                                    "that room key already exists. try a different one.".into(),
-                                );
-                                server.send_message(
-                                    client_id,
-                                    ServerChannel::System,
-                                    bincode::serialize(&msg).unwrap(),
+                                // both seats are filled so the game is on.
+                                room.broadcast(
+                                    &mut server,
+                                    ServerChannel::InGame,
+                                    &room.snapshot(now),
                                 );
                             }
+                        }
+                    } else {
+                        let message = ServerSystemMessage::Error(if room_config.is_none() {
+                            SystemError::NoSuchRoom
+                        } else if lobby.room_mem.contains_key(&client_id) {
+                            SystemError::AlreadyInRoom
+                        } else if let Some(feature) = missing {
+                            SystemError::MissingFeature(feature)
                         } else {
-                            let msg = ServerSystemMessage::Error("you are already in a room. please leave the room before trying to start a new one.".into());
+                            SystemError::RoomFull {
+                                can_watch: room_config.is_some_and(|config| {
+                                    config.spectators == SpectatorPolicy::Open
+                                }),
+                            }
+                        });
+                        send_system(&mut server, client_id, request, message);
+                    }
+                }
+                ClientSystemMessage::WatchRoom(room_key) => {
+                    let room_config = rooms
+                        .iter()
+                        .find(|room| room.id == room_key)
+                        .map(|room| room.config);
+                    let missing = room_config.and_then(|config| {
+                        lobby.missing_feature(client_id, config.variant.feature())
+                    });
+                    if let Some(config) =
+                        room_config.filter(|config| config.spectators == SpectatorPolicy::Open)
+                        && !lobby.room_mem.contains_key(&client_id)
+                        && missing.is_none()
+                    {
+                        lobby.room_mem.insert(client_id, room_key);
+                        let msg = ServerSystemMessage::JoinedRoom(room_key, config);
+                        send_system(&mut server, client_id, request, msg);
+
+                        for mut room in rooms.iter_mut().filter(|room| room.id == room_key) {
+                            room.watch(client_id);
+                            info!(
+                                "{client_id} is watching room {}.",
+                                display_room_id(&room.id)
+                            );
+
                             server.send_message(
                                 client_id,
-                                ServerChannel::System,
-                                bincode::serialize(&msg).unwrap(),
+                                ServerChannel::InGame,
+                                bincode::serialize(&room.snapshot(now)).unwrap(),
                             );
+
+                            let game_end = match room.state {
+                                RoomState::Playing => None,
+                                RoomState::Finished { winner } => {
+                                    Some(ServerInGameMessage::Victory(winner))
+                                }
+                                RoomState::Drawn => Some(ServerInGameMessage::Draw),
+                            };
+
+                            if let Some(message) = game_end {
+                                server.send_message(
+                                    client_id,
+                                    ServerChannel::InGame,
+                                    bincode::serialize(&message).unwrap(),
+                                );
+                            }
                         }
-                    }
-                    ClientSystemMessage::JoinRoom(room_key) => {
-                        // TODO: add room join requesting and the like.
-                        let room_exists =
-                            rooms.iter().position(|room| room.id == room_key).is_some();
-                        if room_exists && !lobby.room_mem.contains_key(&client_id) {
-                            lobby.room_mem.remove(&client_id);
-                            lobby.room_mem.insert(client_id, room_key);
+                    } else {
+                        let msg = ServerSystemMessage::Error(if room_config.is_none() {
+                            SystemError::NoSuchRoom
+                        } else if lobby.room_mem.contains_key(&client_id) {
+                            SystemError::AlreadyInRoom
+                        } else if let Some(feature) = missing {
+                            SystemError::MissingFeature(feature)
                         } else {
-                            let message = bincode::serialize(&if !room_exists {
-                                ServerSystemMessage::Error("that room doen't exist".into())
-                            } else if lobby.room_mem.contains_key(&client_id) {
-                                ServerSystemMessage::Error("you're already in a room".into())
-                            } else {
-                                ServerSystemMessage::Error("can't join that room right now.".into())
-                            });
-                            if let Ok(message) = message {
-                                server.send_message(client_id, ServerChannel::System, message);
-                            } else {
-                                error!("could not serialize JoinRoomFailure message.");
+                            SystemError::NoSpectators
+                        });
+                        send_system(&mut server, client_id, request, msg);
+                    }
+                }
+                ClientSystemMessage::ClockPing { sent } => {
+                    let message = ServerSystemMessage::ClockPong {
+                        sent,
+                        server_time: ServerTime::since(clock.epoch, clock.now()),
+                    };
+                    send_system(&mut server, client_id, request, message);
+                }
+                ClientSystemMessage::Rematch => {
+                    let Some(room_id) = lobby.room_mem.get(&client_id).copied() else {
+                        let msg = ServerSystemMessage::Error(SystemError::NotInRoom);
+                        send_system(&mut server, client_id, request, msg);
+                        continue;
+                    };
+
+                    for mut room in rooms.iter_mut().filter(|room| room.id == room_id) {
+                        if room.request_rematch(client_id, now) {
+                            info!("rematch started in room {}.", display_room_id(&room.id));
+                            for (player, color) in room.seats() {
+                                seat_player(&mut lobby, &mut server, player, color);
                             }
+
+                            room.broadcast(&mut server, ServerChannel::InGame, &room.snapshot(now));
                         }
                     }
                 }
@@ -608,43 +1136,124 @@ fn server_update_system(
         }
 
         while let Some(message) = server.receive_message(client_id, ClientChannel::Game) {
-            if let Ok(command) = bincode::deserialize::<ClientInGameMessage>(&message) {
-                match command {
-                    ClientInGameMessage::Move { from, to } => {
-                        let room_mem = lobby.room_mem.clone();
-                        let room = room_mem.get(&client_id);
-
-                        if let Some(room_id) = room {
-                            if let Some(ref mut player) = lobby.players.get_mut(&client_id) {
-                                rooms.iter_mut().for_each(|mut room| {
-                                    if room.id == room_id.clone() {
-                                        let message = bincode::serialize(
-                                            &room.make_move_for(player, from, to),
-                                        )
-                                        .unwrap();
-                                        server.send_message(
-                                            client_id,
-                                            ServerChannel::InGame,
-                                            message,
-                                        );
-
-                                        // TODO: check for victory.
-                                        // TODO: check for promotion.
-                                    }
-                                });
-                            } else {
-                                let message = bincode::serialize(&ServerSystemMessage::Error(
-                                    "that room has closed. the game ended.".into(),
-                                ))
-                                .unwrap();
-                                server.send_message(client_id, ServerChannel::System, message);
-                            }
+            let command = match decode::<ClientInGameMessage>(&message) {
+                Ok(command) => command,
+                Err(e) => {
+                    if lobby.protocol_violation(&mut server, client_id, e) {
+                        continue 'clients;
+                    }
+
+                    continue;
+                }
+            };
+
+            if !lobby.features.contains_key(&client_id) {
+                let msg = ServerSystemMessage::Error(SystemError::NoHandshake);
+                send_system(&mut server, client_id, None, msg);
+                continue;
+            }
+
+            match command {
+                ClientInGameMessage::Move {
+                    from,
+                    to,
+                    promotion,
+                } => {
+                    let room_mem = lobby.room_mem.clone();
+                    let room = room_mem.get(&client_id);
+
+                    if let Some(room_id) = room {
+                        if lobby.players.contains_key(&client_id) {
+                            rooms.iter_mut().for_each(|mut room| {
+                                if room.id == room_id.clone()
+                                    && let Some(rejection) =
+                                        room.queue_move(client_id, from, to, promotion, now)
+                                {
+                                    server.send_message(
+                                        client_id,
+                                        ServerChannel::InGame,
+                                        bincode::serialize(&rejection).unwrap(),
+                                    );
+                                }
+                            });
                         } else {
-                            let message = bincode::serialize(&ServerSystemMessage::Error(
-                                "you're not in a room. join/start on first".into(),
-                            ))
-                            .unwrap();
-                            server.send_message(client_id, ServerChannel::System, message);
+                            send_system(
+                                &mut server,
+                                client_id,
+                                None,
+                                ServerSystemMessage::Error(SystemError::RoomClosed),
+                            );
+                        }
+                    } else {
+                        send_system(
+                            &mut server,
+                            client_id,
+                            None,
+                            ServerSystemMessage::Error(SystemError::NotInRoom),
+                        );
+                    }
+                }
+                ClientInGameMessage::RequestSnapshot => {
+                    let Some(room_id) = lobby.room_mem.get(&client_id) else {
+                        send_system(
+                            &mut server,
+                            client_id,
+                            None,
+                            ServerSystemMessage::Error(SystemError::NotInRoom),
+                        );
+                        continue;
+                    };
+
+                    for room in rooms.iter().filter(|room| room.id == *room_id) {
+                        let snapshot = bincode::serialize(&room.snapshot(now)).unwrap();
+                        server.send_message(client_id, ServerChannel::InGame, snapshot);
+                    }
+                }
+                ClientInGameMessage::Premove(premove) => {
+                    if let Some(feature) = lobby.missing_feature(client_id, Some(Feature::Premoves))
+                    {
+                        send_system(
+                            &mut server,
+                            client_id,
+                            None,
+                            ServerSystemMessage::Error(SystemError::MissingFeature(feature)),
+                        );
+                        continue;
+                    }
+
+                    let Some(room_id) = lobby.room_mem.get(&client_id) else {
+                        send_system(
+                            &mut server,
+                            client_id,
+                            None,
+                            ServerSystemMessage::Error(SystemError::NotInRoom),
+                        );
+                        continue;
+                    };
+
+                    for mut room in rooms.iter_mut().filter(|room| room.id == *room_id) {
+                        if let Some(rejection) =
+                            room.queue_premove(&mut server, client_id, premove, now)
+                        {
+                            server.send_message(
+                                client_id,
+                                ServerChannel::InGame,
+                                bincode::serialize(&rejection).unwrap(),
+                            );
+                        }
+                    }
+                }
+                ClientInGameMessage::CancelPremove { from } => {
+                    if let Some(room_id) = lobby.room_mem.get(&client_id) {
+                        for mut room in rooms.iter_mut().filter(|room| room.id == *room_id) {
+                            room.cancel_premove(&mut server, client_id, from);
+                        }
+                    }
+                }
+                ClientInGameMessage::ListPremoves => {
+                    if let Some(room_id) = lobby.room_mem.get(&client_id) {
+                        for room in rooms.iter().filter(|room| room.id == *room_id) {
+                            room.send_premoves(&mut server, client_id);
                         }
                     }
                 }
@@ -652,3 +1261,55 @@ fn server_update_system(
         }
     }
 }
+
+/// sends `message` to `client_id` on the system channel, as the answer to `request` if it is
+/// one. the handshake is sent without the request so any version of the client can read it.
+fn send_system(
+    server: &mut RenetServer,
+    client_id: ClientId,
+    request: Option<RequestId>,
+    message: ServerSystemMessage,
+) {
+    let response = match message {
+        ServerSystemMessage::Handshake(reply) => SystemResponse::Handshake(reply),
+        message => SystemResponse::Reply { request, message },
+    };
+    server.send_message(
+        client_id,
+        ServerChannel::System,
+        bincode::serialize(&response).unwrap(),
+    );
+}
+
+/// records a seated player's color in the lobby and lets them know which color they are.
+fn seat_player(
+    lobby: &mut ServerLobby,
+    server: &mut RenetServer,
+    client_id: ClientId,
+    color: PlayerColor,
+) {
+    if let Some(player) = lobby.players.get_mut(&client_id) {
+        player.color = color;
+    }
+
+    let message = bincode::serialize(&ServerInGameMessage::Seated(color)).unwrap();
+    server.send_message(client_id, ServerChannel::InGame, message);
+}
+
+/// makes premoves whose piece is ready and resolves each room's queued moves once their
+/// window has closed.
+fn resolve_move_queues(
+    mut rooms: Query<&mut Room>,
+    mut server: ResMut<RenetServer>,
+    clock: Res<GameClock>,
+) {
+    let now = clock.now();
+
+    for mut room in rooms.iter_mut() {
+        room.land_flights(&mut server, now);
+        room.notify_ready(&mut server, now);
+        room.fire_premoves(&mut server, now);
+        room.resolve_queue(&mut server, now);
+        room.check_time_limit(&mut server, now);
+    }
+}
@@ -6,26 +6,53 @@ use bevy::{
 use bevy_egui::EguiPlugin;
 use bevy_renet::RenetClientPlugin;
 use client::{
+    components::{
+        handshake::{Handshake, handshake_accepted},
+        pending_requests::PendingRequests,
+        server_clock::ServerClock,
+    },
     events::{
-        game_end::GameEnd, invalid_move::InvalidMoveNotif, new_error::NewError,
-        opponent_capture::OpponentCaptureNotif, opponent_move::OpponentMoveNotif,
-        player_capture::PlayerCaptureNotif, player_move::PlayerMoveNotif, room_change::RoomChange,
+        board_sync::BoardSync, game_end::GameEnd, invalid_move::InvalidMoveNotif,
+        new_error::NewError, opponent_capture::OpponentCaptureNotif,
+        opponent_move::OpponentMoveNotif, piece_arrived::PieceArrived, piece_ready::PieceReady,
+        pieces_traded::PiecesTraded, player_capture::PlayerCaptureNotif,
+        player_move::PlayerMoveNotif, protocol_mismatch::ProtocolMismatch, room_change::RoomChange,
+        seated::Seated,
     },
     plugins::{in_game::InGamePlugin, setup_network_plugin::SetupNetwork},
+    settings::{ClientSettings, USAGE},
     states::game_state::GameState,
     systems::{
-        Connected, enter_room_select::enter_select_room, get_room_list::get_rooms_list,
-        handle_error::handle_error_event, handle_invalid_move::handle_invalid_move_event,
-        handle_room_change::handle_room_change_event, recv_in_game_messages::recv_in_game_messages,
-        recv_in_room_messages::recv_in_room_messages, recv_system_messages::recv_system_messages,
-        setup_game_camera::setup_camera, update_visualizer::update_visulizer_system,
+        Connected, draw_post_game::draw_post_game, draw_protocol_mismatch::draw_protocol_mismatch,
+        enter_room_select::enter_select_room, get_room_list::get_rooms_list,
+        handle_error::handle_error_event, handle_game_end::handle_game_end_event,
+        handle_invalid_move::handle_invalid_move_event,
+        handle_protocol_mismatch::handle_protocol_mismatch_event,
+        handle_room_change::handle_room_change_event, handle_seated::handle_seated_event,
+        recv_in_game_messages::recv_in_game_messages, recv_in_room_messages::recv_in_room_messages,
+        recv_system_messages::recv_system_messages, retry_requests::retry_requests,
+        send_hello::send_hello, setup_game_camera::setup_camera,
+        sync_server_clock::sync_server_clock, update_visualizer::update_visulizer_system,
     },
 };
+use real_time_chess::settings::{SettingsError, load_settings};
 use renet_visualizer::{RenetClientVisualizer, RenetVisualizerStyle};
 
 pub mod client;
 
 fn main() {
+    let settings: ClientSettings = match load_settings(std::env::args().skip(1)) {
+        Ok(settings) => settings,
+        Err(SettingsError::Help) => {
+            print!("{USAGE}");
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
     App::new()
         .add_plugins(
             DefaultPlugins
@@ -43,7 +70,7 @@ fn main() {
                     ..Default::default()
                 })
                 .set(LogPlugin {
-                    // level: bevy::log::Level::DEBUG,
+                    level: settings.log_level.level(),
                     // filter: "debug,wgpu_core=warn,wgpu_hal=warn".into(),
                     ..default()
                 })
@@ -54,9 +81,15 @@ fn main() {
         .add_plugins(FrameTimeDiagnosticsPlugin)
         .add_plugins(LogDiagnosticsPlugin::default())
         .add_plugins(EguiPlugin)
-        .add_plugins(SetupNetwork)
+        .add_plugins(SetupNetwork {
+            server_addr: settings.server,
+            bind_addr: settings.bind,
+        })
         .add_plugins(InGamePlugin)
         .init_state::<GameState>()
+        .init_resource::<ServerClock>()
+        .init_resource::<Handshake>()
+        .init_resource::<PendingRequests>()
         .add_event::<InvalidMoveNotif>()
         .add_event::<PlayerCaptureNotif>()
         .add_event::<OpponentCaptureNotif>()
@@ -65,23 +98,42 @@ fn main() {
         .add_event::<GameEnd>()
         .add_event::<NewError>()
         .add_event::<RoomChange>()
+        .add_event::<Seated>()
+        .add_event::<BoardSync>()
+        .add_event::<PiecesTraded>()
+        .add_event::<PieceArrived>()
+        .add_event::<PieceReady>()
+        .add_event::<ProtocolMismatch>()
         .add_systems(
             Update,
             (
                 // client_send_input,
                 // client_send_player_commands,
+                send_hello,
+                retry_requests,
+                sync_server_clock.run_if(handshake_accepted),
                 recv_system_messages,
                 recv_in_room_messages,
                 recv_in_game_messages,
                 handle_error_event,
+                handle_game_end_event,
                 handle_invalid_move_event,
+                handle_protocol_mismatch_event,
                 handle_room_change_event,
+                handle_seated_event,
                 update_visulizer_system,
-                enter_select_room.run_if(in_state(GameState::Startup)),
+                enter_select_room
+                    .run_if(in_state(GameState::Startup))
+                    .run_if(handshake_accepted),
             )
                 .in_set(Connected),
         )
         .add_systems(OnEnter(GameState::RoomSelect), get_rooms_list)
+        .add_systems(
+            Update,
+            draw_protocol_mismatch.run_if(in_state(GameState::ProtocolMismatch)),
+        )
+        .add_systems(Update, draw_post_game.run_if(in_state(GameState::PostGame)))
         .insert_resource(RenetClientVisualizer::<200>::new(
             RenetVisualizerStyle::default(),
         ))
@@ -1,15 +1,73 @@
 use bevy::prelude::*;
 use bevy_renet::renet::{ChannelConfig, ClientId, ConnectionConfig, SendType};
-use serde::{Deserialize, Serialize};
-use std::{f32::consts::PI, time::Duration};
+use bincode::Options;
+use rules::MoveError;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
 
 pub mod components;
 pub mod events;
 pub mod plugins;
+pub mod rules;
+pub mod settings;
 pub mod states;
 pub mod systems;
 
 pub const PROTOCOL_ID: u64 = 7;
+/// the version of the messages in this file, checked by the handshake. bump it whenever they
+/// change in a way an older build couldn't read.
+pub const PROTOCOL_VERSION: u32 = 3;
+/// the optional parts of the protocol this build supports.
+pub const FEATURES: &[Feature] = &[Feature::TravelTime, Feature::Premoves];
+
+/// an optional part of the protocol. the client lists the ones it supports in its `Hello`
+/// and the server answers with the ones it will use. new features go above `Unknown`, and
+/// can't carry any data, so that older builds can still read a `Hello` listing them.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// rooms played with `Variant::Travel`.
+    TravelTime,
+    /// queueing moves for pieces that are still on cooldown.
+    Premoves,
+    /// talking to the other people in a room. nothing supports this yet.
+    Chat,
+    /// a feature from a newer build that this one doesn't know about. never sent, and never
+    /// supported, so the handshake drops it. has to stay the last variant.
+    #[serde(other)]
+    Unknown,
+}
+
+/// the server's answer to a `Hello`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HandshakeReply {
+    /// the client can go ahead, using only these features.
+    Accepted {
+        features: Vec<Feature>,
+    },
+    Rejected(HandshakeRejection),
+}
+
+/// why the server won't talk to a client.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HandshakeRejection {
+    /// the client speaks a different version of the protocol.
+    UnsupportedVersion { client: u32, server: u32 },
+}
+
+impl fmt::Display for HandshakeRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion { client, server } => write!(
+                f,
+                "the server speaks version {server} of the protocol but this client speaks \
+                 version {client}. update whichever is older."
+            ),
+        }
+    }
+}
 
 pub type Location = (Rank, File);
 pub type RoomID = [char; 4];
@@ -19,32 +77,63 @@ pub fn display_room_id(id: &RoomID) -> String {
     format!("{}-{}-{}-{}", id[0], id[1], id[2], id[3])
 }
 
-#[derive(Debug, Clone, Copy, Component, Serialize, Deserialize, PartialEq, PartialOrd)]
-pub struct Slope {
-    pub rise: f32,
-    pub run: f32,
+/// the displacement of a move measured in whole squares. `rank` is the change along the
+/// lettered axis and `file` is the change along the numbered axis.
+#[derive(Debug, Clone, Copy, Component, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct MoveDelta {
+    pub rank: i8,
+    pub file: i8,
 }
 
-impl Slope {
-    pub fn to_degrees(&self) -> f32 {
-        if self.run == 0.0 && self.rise > 0.0 {
-            90.0
-        } else if self.run == 0.0 && self.rise < 0.0 {
-            270.0
-        } else {
-            (self.rise / self.run).atan() * 180.0 / PI
+impl MoveDelta {
+    pub fn new(from: &Location, to: &Location) -> Self {
+        let (from_rank, from_file): (usize, usize) = (from.0.into(), from.1.into());
+        let (to_rank, to_file): (usize, usize) = (to.0.into(), to.1.into());
+
+        Self {
+            rank: to_rank as i8 - from_rank as i8,
+            file: to_file as i8 - from_file as i8,
         }
     }
+
+    /// the number of squares travelled, counting a diagonal step as one square.
+    pub fn distance(&self) -> i8 {
+        self.rank.abs().max(self.file.abs())
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.rank == 0 && self.file == 0
+    }
+
+    /// true when the move is purely along one axis.
+    pub fn is_straight(&self) -> bool {
+        !self.is_null() && (self.rank == 0 || self.file == 0)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        !self.is_null() && self.rank.abs() == self.file.abs()
+    }
+
+    pub fn is_knight_jump(&self) -> bool {
+        let (rank, file) = (self.rank.abs(), self.file.abs());
+
+        (rank == 1 && file == 2) || (rank == 2 && file == 1)
+    }
+
+    /// the single square step that, repeated `distance()` times, makes up this move. only
+    /// meaningful for straight and diagonal moves.
+    pub fn step(&self) -> (i8, i8) {
+        (self.rank.signum(), self.file.signum())
+    }
 }
 
 #[derive(Debug, Clone, Copy, Component, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Player {
     pub id: ClientId,
     pub color: PlayerColor,
-    pub cooldown: Duration,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum File {
     One,
     Two,
@@ -56,6 +145,20 @@ pub enum File {
     Eight,
 }
 
+impl File {
+    /// every file, ordered by board index.
+    pub const ALL: [File; 8] = [
+        Self::One,
+        Self::Two,
+        Self::Three,
+        Self::Four,
+        Self::Five,
+        Self::Six,
+        Self::Seven,
+        Self::Eight,
+    ];
+}
+
 impl Into<usize> for File {
     fn into(self) -> usize {
         match self {
@@ -71,7 +174,7 @@ impl Into<usize> for File {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum Rank {
     A,
     B,
@@ -83,6 +186,20 @@ pub enum Rank {
     H,
 }
 
+impl Rank {
+    /// every rank, ordered by board index.
+    pub const ALL: [Rank; 8] = [
+        Self::A,
+        Self::B,
+        Self::C,
+        Self::D,
+        Self::E,
+        Self::F,
+        Self::G,
+        Self::H,
+    ];
+}
+
 impl Into<usize> for Rank {
     fn into(self) -> usize {
         match self {
@@ -113,13 +230,75 @@ pub enum ClientInGameMessage {
     Move {
         from: (Rank, File),
         to: (Rank, File),
+        /// what to promote a pawn to if this move takes it to the far side of the board.
+        /// defaults to a queen when left empty.
+        promotion: Option<ChessPiece>,
     },
+    /// asks for a `BoardSnapshot`, used to resync after missing a move.
+    RequestSnapshot,
+    /// queues a move to be made as soon as the piece on `from` is ready. replaces any premove
+    /// already queued for that piece. a `Move` for a piece that is still on cooldown is rejected
+    /// instead.
+    Premove(Premove),
+    /// cancels the premove queued for the piece on `from`.
+    CancelPremove { from: Location },
+    /// asks for the premoves the sender has queued.
+    ListPremoves,
 }
+
+/// a move waiting for its piece to come off cooldown.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Premove {
+    pub from: Location,
+    pub to: Location,
+    pub promotion: Option<ChessPiece>,
+}
+/// picked by the client for each `SystemRequest` so it can tell which request a reply answers.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+pub struct RequestId(pub u32);
+
+/// what the client sends on `ClientChannel::System`. `Hello` is laid out exactly like
+/// `ClientSystemMessage::Hello` so that any version of the server can read the handshake, and
+/// everything after it is tagged with the id of the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SystemRequest {
+    /// has to stay the first variant and match `ClientSystemMessage::Hello` field for field.
+    Hello {
+        version: u32,
+        features: Vec<Feature>,
+    },
+    Request {
+        id: RequestId,
+        message: ClientSystemMessage,
+    },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientSystemMessage {
-    StartRoom(RoomID),
+    /// the first message a client sends. nothing else is answered until the server accepts
+    /// it. this has to stay the first variant and keep its fields so that any version of the
+    /// server can read it.
+    Hello {
+        version: u32,
+        features: Vec<Feature>,
+    },
+    /// starts a new room with the sender as its host, seated as picked by the `SeatChoice` and
+    /// played by the rules in the `RoomConfig`, or the server's default rules if there isn't one.
+    StartRoom(RoomID, SeatChoice, Option<RoomConfig>),
     JoinRoom(RoomID),
+    /// joins a room as an observer. observers see every move but can't make any.
+    WatchRoom(RoomID),
     ListRooms,
+    /// asks to play the finished game in the sender's room again. the game restarts, with the
+    /// players swapping colors, once both players have asked.
+    Rematch,
+    /// asks for the server's time, to work out how far the client's clock is from it. `sent` is
+    /// the client's own time when it was sent and comes back untouched in the `ClockPong`.
+    ClockPing {
+        sent: Duration,
+    },
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Resource)]
@@ -128,10 +307,454 @@ pub enum PlayerColor {
     White,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl PlayerColor {
+    /// the color of the other player.
+    pub fn opponent(&self) -> Self {
+        match self {
+            Self::Black => Self::White,
+            Self::White => Self::Black,
+        }
+    }
+}
+
+/// which color the host of a new room plays as. whoever joins second gets the other one.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SeatChoice {
+    Color(PlayerColor),
+    #[default]
+    Random,
+}
+
+/// how pieces get from one square to another.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Variant {
+    /// pieces move instantly.
+    #[default]
+    Classic,
+    /// pieces take `per_square` to cross each square they travel.
+    Travel { per_square: Duration },
+}
+
+impl Variant {
+    /// the feature a client needs to play or watch this variant, if any.
+    pub fn feature(&self) -> Option<Feature> {
+        match self {
+            Self::Classic => None,
+            Self::Travel { .. } => Some(Feature::TravelTime),
+        }
+    }
+}
+
+/// how long pieces have to wait between moves in a room. each one is played by the matching
+/// `rules::CooldownPolicy`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CooldownRule {
+    /// pieces can't move for the first part of the cooldown and moving them anyway makes
+    /// every one of the player's pieces wait longer from then on. how long and how much longer
+    /// is set by the `PenaltyFactors`.
+    Penalty {
+        base: Duration,
+        factors: PenaltyFactors,
+    },
+    /// every move puts the piece on the same cooldown.
+    Flat { cooldown: Duration },
+    /// each kind of piece has its own cooldown.
+    PerPiece {
+        pawn: Duration,
+        knight: Duration,
+        bishop: Duration,
+        rook: Duration,
+        queen: Duration,
+        king: Duration,
+    },
+    /// a short cooldown per piece plus a pool of `capacity` moves shared by all of a player's
+    /// pieces, which gets one move back every `recovery`.
+    Stamina {
+        cooldown: Duration,
+        capacity: u32,
+        recovery: Duration,
+    },
+}
+
+impl Default for CooldownRule {
+    fn default() -> Self {
+        Self::Penalty {
+            base: rules::DEFAULT_COOLDOWN,
+            factors: PenaltyFactors::default(),
+        }
+    }
+}
+
+/// `num / den` of a duration.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Fraction {
+    pub num: u32,
+    pub den: u32,
+}
+
+impl Fraction {
+    pub const fn new(num: u32, den: u32) -> Self {
+        Self { num, den }
+    }
+
+    /// this fraction of `duration`. the division comes first, so `3/4` of a cooldown is exactly
+    /// three of its quarters. saturates at `Duration::MAX` instead of overflowing, and treats a
+    /// zero denominator as an endless fraction.
+    pub fn of(&self, duration: Duration) -> Duration {
+        duration
+            .checked_div(self.den)
+            .and_then(|part| part.checked_mul(self.num))
+            .unwrap_or(Duration::MAX)
+    }
+}
+
+/// how hard `CooldownRule::Penalty` comes down on moving pieces early. every fraction is of
+/// the cooldown of the piece that was moved.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PenaltyFactors {
+    /// the part of the cooldown a piece can't move in at all. moving it in the rest of the
+    /// cooldown is allowed but penalised by `early`.
+    pub locked: Fraction,
+    /// added to the player's cooldown when they try to move a piece that's locked.
+    pub rejected: Fraction,
+    /// added to the player's cooldown when they move a piece after it unlocks but before its
+    /// cooldown is over.
+    pub early: Fraction,
+}
+
+impl Default for PenaltyFactors {
+    fn default() -> Self {
+        Self {
+            locked: Fraction::new(3, 4),
+            rejected: Fraction::new(1, 3),
+            early: Fraction::new(1, 4),
+        }
+    }
+}
+
+/// how long a game can go on for.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TimeControl {
+    /// the game goes on until a king is captured.
+    #[default]
+    Unlimited,
+    /// the game is a draw if no king has been captured this long after both players sat down.
+    Limit(Duration),
+}
+
+/// who can find a room.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Visibility {
+    /// the room shows up in the room listing.
+    #[default]
+    Public,
+    /// the room is left out of the listing. anyone with its key can still join.
+    Unlisted,
+}
+
+/// whether a room can be watched.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SpectatorPolicy {
+    #[default]
+    Open,
+    /// only the players can be in the room.
+    Closed,
+}
+
+/// the shortest cooldown a room can be configured with.
+pub const MIN_COOLDOWN: Duration = Duration::from_millis(100);
+/// the longest cooldown a room can be configured with, and the longest penalties can make it.
+pub const MAX_COOLDOWN: Duration = Duration::from_secs(60);
+/// the longest a piece can be configured to take to cross one square.
+pub const MAX_TRAVEL_TIME: Duration = Duration::from_secs(5);
+/// the shortest time limit a game can have.
+pub const MIN_TIME_LIMIT: Duration = Duration::from_secs(30);
+
+/// the rules a room is played by. picked by whoever starts the room and shown to everyone
+/// before they join it. rules left out of a config file keep their defaults.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RoomConfig {
+    pub cooldown: CooldownRule,
+    pub variant: Variant,
+    pub time_control: TimeControl,
+    pub visibility: Visibility,
+    pub spectators: SpectatorPolicy,
+}
+
+impl RoomConfig {
+    /// checks that the rules make for a game that can be played.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let cooldowns = match self.cooldown {
+            CooldownRule::Penalty { base, factors } => {
+                let fractions = [factors.locked, factors.rejected, factors.early];
+
+                // a penalty bigger than the whole cooldown would make it grow out of hand.
+                if let Some(fraction) = fractions
+                    .into_iter()
+                    .find(|fraction| fraction.den == 0 || fraction.num > fraction.den)
+                {
+                    return Err(ConfigError::BadFraction(fraction));
+                }
+
+                vec![base]
+            }
+            CooldownRule::Flat { cooldown } => vec![cooldown],
+            CooldownRule::PerPiece {
+                pawn,
+                knight,
+                bishop,
+                rook,
+                queen,
+                king,
+            } => vec![pawn, knight, bishop, rook, queen, king],
+            CooldownRule::Stamina {
+                cooldown,
+                capacity,
+                recovery,
+            } => {
+                if capacity == 0 {
+                    return Err(ConfigError::NoStamina);
+                }
+
+                vec![cooldown, recovery]
+            }
+        };
+
+        if let Some(cooldown) = cooldowns
+            .into_iter()
+            .find(|cooldown| !(MIN_COOLDOWN..=MAX_COOLDOWN).contains(cooldown))
+        {
+            return Err(ConfigError::CooldownOutOfRange(cooldown));
+        }
+
+        if let Variant::Travel { per_square } = self.variant
+            && (per_square.is_zero() || per_square > MAX_TRAVEL_TIME)
+        {
+            return Err(ConfigError::TravelTimeOutOfRange(per_square));
+        }
+
+        if let TimeControl::Limit(limit) = self.time_control
+            && limit < MIN_TIME_LIMIT
+        {
+            return Err(ConfigError::TimeLimitTooShort(limit));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for RoomConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cooldown {
+            CooldownRule::Penalty { base, .. } => write!(f, "{base:?} cooldown with penalties")?,
+            CooldownRule::Flat { cooldown } => write!(f, "{cooldown:?} cooldown")?,
+            CooldownRule::PerPiece { .. } => write!(f, "per piece cooldowns")?,
+            CooldownRule::Stamina {
+                capacity, recovery, ..
+            } => write!(f, "{capacity} stamina, one back every {recovery:?}")?,
+        }
+
+        if let Variant::Travel { per_square } = self.variant {
+            write!(f, ", {per_square:?} per square travel")?;
+        }
+
+        if let TimeControl::Limit(limit) = self.time_control {
+            write!(f, ", {limit:?} time limit")?;
+        }
+
+        if self.spectators == SpectatorPolicy::Closed {
+            write!(f, ", no spectators")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// why a `RoomConfig` was turned down.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ConfigError {
+    CooldownOutOfRange(Duration),
+    /// a penalty fraction divides by zero or is bigger than one.
+    BadFraction(Fraction),
+    NoStamina,
+    TravelTimeOutOfRange(Duration),
+    TimeLimitTooShort(Duration),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CooldownOutOfRange(cooldown) => write!(
+                f,
+                "cooldowns have to be between {MIN_COOLDOWN:?} and {MAX_COOLDOWN:?}, not \
+                 {cooldown:?}."
+            ),
+            Self::BadFraction(Fraction { num, den }) => {
+                write!(f, "{num}/{den} isn't a usable penalty factor.")
+            }
+            Self::NoStamina => write!(f, "players need at least one point of stamina."),
+            Self::TravelTimeOutOfRange(per_square) => write!(
+                f,
+                "travel time has to be more than zero and at most {MAX_TRAVEL_TIME:?} a square, \
+                 not {per_square:?}."
+            ),
+            Self::TimeLimitTooShort(limit) => write!(
+                f,
+                "a time limit has to be at least {MIN_TIME_LIMIT:?}, not {limit:?}."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// why the server wouldn't make a move or queue a premove.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MoveRejection {
+    /// the move breaks the rules of the game.
+    Rules(MoveError),
+    /// the client is only watching the room.
+    Spectating,
+    /// the client is in the room but doesn't have a seat.
+    NotSeated,
+    /// a premove was waiting for its piece, but couldn't be made once the piece was ready.
+    PremoveDropped {
+        from: Location,
+        to: Location,
+        reason: MoveError,
+    },
+}
+
+impl fmt::Display for MoveRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rules(e) => write!(f, "{e}"),
+            Self::Spectating => write!(
+                f,
+                "you're watching this room. spectators can't move peices."
+            ),
+            Self::NotSeated => write!(
+                f,
+                "you don't have a seat in this room. only its players can move peices."
+            ),
+            Self::PremoveDropped { from, to, reason } => {
+                write!(f, "dropped the premove from {from:?} to {to:?}. {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MoveRejection {}
+
+/// why the server turned down something a client asked for outside of a game.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SystemError {
+    /// the client has to be in a room to do that.
+    NotInRoom,
+    /// the client has to leave its room before it can start or join another.
+    AlreadyInRoom,
+    NoSuchRoom,
+    RoomTaken,
+    /// both seats are taken. `can_watch` is set when the room lets spectators in.
+    RoomFull {
+        can_watch: bool,
+    },
+    NoSpectators,
+    /// the room was started with rules that don't make a playable game.
+    BadConfig(ConfigError),
+    /// the client's room is gone, usually because its game ended.
+    RoomClosed,
+    /// the client hasn't sent a `Hello` yet, or it was rejected.
+    NoHandshake,
+    /// the client didn't say it supports something it needs for that.
+    MissingFeature(Feature),
+}
+
+impl fmt::Display for SystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInRoom => write!(f, "you're not in a room. join/start on first"),
+            Self::AlreadyInRoom => write!(
+                f,
+                "you are already in a room. please leave the room before trying to start or join \
+                 a new one."
+            ),
+            Self::NoSuchRoom => write!(f, "that room doen't exist"),
+            Self::RoomTaken => write!(f, "that room key already exists. try a different one."),
+            Self::RoomFull { can_watch: true } => {
+                write!(f, "that room is full. you can still watch it.")
+            }
+            Self::RoomFull { can_watch: false } => write!(f, "that room is full."),
+            Self::NoSpectators => write!(f, "that room doesn't allow spectators"),
+            Self::BadConfig(e) => write!(f, "can't start a room with those rules. {e}"),
+            Self::RoomClosed => write!(f, "that room has closed. the game ended."),
+            Self::NoHandshake => write!(f, "say hello to the server first."),
+            Self::MissingFeature(feature) => {
+                write!(f, "you need a client that supports {feature:?} for that.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SystemError {}
+
+/// a point in time on the server's clock, counted from when the server started. clients turn
+/// these into their own time using the offset they estimate with `ClockPing`.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct ServerTime(pub Duration);
+
+impl ServerTime {
+    /// `at` on a server that started at `epoch`.
+    pub fn since(epoch: Instant, at: Instant) -> Self {
+        Self(at.saturating_duration_since(epoch))
+    }
+}
+
+/// a piece that is travelling between squares.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FlightInfo {
+    /// the travelling piece. `pos` is the square it took off from.
+    pub piece: PlacedPiece,
+    pub to: Location,
+    pub departed: ServerTime,
+    pub arrives_at: ServerTime,
+}
+
+/// a piece and the square it's standing on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlacedPiece {
+    pub pos: Location,
+    pub piece: ChessPiece,
+    pub color: PlayerColor,
+}
+
+/// what the room listing shows about a room.
+#[derive(Debug, Clone, Copy, Component, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RoomInfo {
+    pub id: RoomID,
+    /// how many of the two seats are taken.
+    pub players: u8,
+    pub spectators: usize,
+    pub config: RoomConfig,
+}
+
+/// how far a piece has come off its cooldown.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ReadyStage {
+    /// the piece can move again, but moving now would land its player in the penalty window.
+    Unlocked,
+    /// the whole cooldown is over and the piece can move without a penalty.
+    Ready,
+}
+
+/// when the piece standing on `pos` comes off cooldown.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Cooldown {
-    pos: Location,
-    time_left: Duration,
+    pub pos: Location,
+    pub ready_at: ServerTime,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -146,33 +769,119 @@ pub enum ServerInRoomMessage {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServerInGameMessage {
+    /// the client was given a seat in the room and plays as this color.
+    Seated(PlayerColor),
+    /// the whole board. sent when a game starts, when someone joins or starts watching a game
+    /// and whenever a client asks for one.
+    BoardSnapshot {
+        /// the `seq` of the last move applied to this board.
+        seq: u64,
+        pieces: Vec<PlacedPiece>,
+        /// the pieces that are still on cooldown.
+        cooldowns: Vec<Cooldown>,
+        /// the pieces that are travelling. these aren't in `pieces`.
+        flights: Vec<FlightInfo>,
+    },
     /// the move was recieved and made successfully.
     MoveRecv {
+        /// counts up by one with every move made in the room. a client that sees a gap has
+        /// missed a move and should ask for a `BoardSnapshot`.
+        seq: u64,
         player: PlayerColor,
         from: Location,
         to: Location,
         capture: bool,
+        /// the square the captured piece was removed from. differs from `to` for en passant.
+        captured_at: Option<Location>,
+        /// the piece a pawn was promoted to by this move.
+        promotion: Option<ChessPiece>,
+        /// when the king castled, where its rook moved from and to.
+        rook_move: Option<(Location, Location)>,
+        /// how long the piece's cooldown is.
         cooldown: Duration,
+        /// when the piece's cooldown is over, penalty window included.
+        ready_at: ServerTime,
+        /// set when the room has travel time. the piece is in the air until it lands at this
+        /// time, which is reported by `Arrived`. until then `capture`, `captured_at`,
+        /// `promotion` and `rook_move` are left empty.
+        arrives_at: Option<ServerTime>,
     },
-    /// the peice can't move like that caries the message which describes how/why that move
-    /// was invalid.  
-    InvalidMove(String),
+    /// a travelling piece landed.
+    Arrived {
+        seq: u64,
+        player: PlayerColor,
+        from: Location,
+        to: Location,
+        captured_at: Option<Location>,
+        promotion: Option<ChessPiece>,
+        rook_move: Option<(Location, Location)>,
+        /// where the enemy pieces this one intercepted took off from. they are off the board.
+        intercepted: Vec<Location>,
+        /// when the landed piece's cooldown is over.
+        ready_at: ServerTime,
+    },
+    /// opposing pieces moved to the same square at the same time and bounced off each other.
+    /// none of them moved.
+    Bounced {
+        to: Location,
+        from: Vec<Location>,
+    },
+    /// two opposing pieces moved onto each other's square at the same time and captured each
+    /// other. both are removed from the board.
+    Traded {
+        seq: u64,
+        squares: (Location, Location),
+    },
+    /// the piece on `square` just came off cooldown on the server. sent once when the piece
+    /// can move again and once more when its penalty window is over, unless both happen at once.
+    PieceReady {
+        square: Location,
+        stage: ReadyStage,
+    },
+    /// every premove the client has queued. sent whenever one is queued, cancelled, made or
+    /// dropped, and when asked for.
+    Premoves(Vec<Premove>),
+    /// the move or premove was turned down, and why.
+    InvalidMove(MoveRejection),
     /// a player captured the king
     Victory(PlayerColor),
     Draw,
+    /// the opponent left the game before it finished, so the player still seated wins.
     OpponentDisconect,
 }
 
+/// what the server sends on `ServerChannel::System`. like `SystemRequest` the handshake comes
+/// first and is laid out exactly like `ServerSystemMessage::Handshake`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SystemResponse {
+    /// has to stay the first variant.
+    Handshake(HandshakeReply),
+    Reply {
+        /// the request this answers. `None` when the server is telling the client something on
+        /// its own, like an error with a move it sent on `ClientChannel::Game`.
+        request: Option<RequestId>,
+        message: ServerSystemMessage,
+    },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServerSystemMessage {
+    /// answers a `Hello`. like `Hello` it has to stay the first variant so that any version of
+    /// the client can read it.
+    Handshake(HandshakeReply),
     /// a list of the rooms available to join.
-    ListRooms(Vec<RoomID>),
-    /// Misc error.
-    Error(String),
-    /// notifies a client that they joined a room.
-    JoinedRoom(RoomID),
+    ListRooms(Vec<RoomInfo>),
+    /// something the client asked for was turned down, and why.
+    Error(SystemError),
+    /// notifies a client that they joined a room, and the rules it's played by.
+    JoinedRoom(RoomID, RoomConfig),
     /// notifies a client that they left a room.
     LeftRoom(RoomID),
+    /// answers a `ClockPing`. `server_time` is the server's time when it was answered.
+    ClockPong {
+        sent: Duration,
+        server_time: ServerTime,
+    },
 }
 
 pub enum ClientChannel {
@@ -231,7 +940,9 @@ impl ServerChannel {
             ChannelConfig {
                 channel_id: Self::System.into(),
                 max_memory_usage_bytes: 10 * 1024 * 1024,
-                send_type: SendType::Unreliable,
+                send_type: SendType::ReliableOrdered {
+                    resend_time: Duration::from_millis(200),
+                },
             },
             ChannelConfig {
                 channel_id: Self::InGame.into(),
@@ -256,3 +967,69 @@ pub fn connection_config() -> ConnectionConfig {
         server_channels_config: ServerChannel::channels_config(),
     }
 }
+
+/// the largest message either side will try to decode.
+pub const MAX_MESSAGE_SIZE: u64 = 64 * 1024;
+
+/// why a message that came in over the network couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    Empty,
+    /// the message claims to hold more than `MAX_MESSAGE_SIZE` bytes.
+    TooLarge,
+    /// the message ended before everything in it was read.
+    Truncated,
+    /// bytes were left over after the message was read.
+    TrailingBytes,
+    /// the bytes don't make a message of the expected type, usually because the other side
+    /// is running a different version of the game.
+    Invalid(String),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the message was empty."),
+            Self::TooLarge => write!(f, "the message is larger than {MAX_MESSAGE_SIZE} bytes."),
+            Self::Truncated => write!(f, "the message was cut short."),
+            Self::TrailingBytes => write!(f, "the message had bytes left over at the end."),
+            Self::Invalid(reason) => write!(f, "the message couldn't be read. {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<bincode::Error> for DecodeError {
+    fn from(e: bincode::Error) -> Self {
+        match *e {
+            bincode::ErrorKind::Io(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                Self::Truncated
+            }
+            bincode::ErrorKind::SizeLimit => Self::TooLarge,
+            // bincode doesn't have its own error for this.
+            bincode::ErrorKind::Custom(reason)
+                if reason == "Slice had bytes remaining after deserialization" =>
+            {
+                Self::TrailingBytes
+            }
+            e => Self::Invalid(e.to_string()),
+        }
+    }
+}
+
+/// reads a message sent with `bincode::serialize`. unlike `bincode::deserialize` this refuses
+/// messages with anything left over at the end, or that would need more than
+/// `MAX_MESSAGE_SIZE` bytes to decode.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DecodeError> {
+    if bytes.is_empty() {
+        return Err(DecodeError::Empty);
+    }
+
+    let options = bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_limit(MAX_MESSAGE_SIZE)
+        .reject_trailing_bytes();
+
+    options.deserialize(bytes).map_err(DecodeError::from)
+}
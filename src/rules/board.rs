@@ -0,0 +1,156 @@
+use crate::{ChessPiece, File, Location, PlacedPiece, PlayerColor, Rank};
+use std::{
+    ops::{Index, IndexMut},
+    time::{Duration, Instant},
+};
+
+/// a piece on the board along with when it last moved and the cooldown it was put on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardPiece {
+    pub piece: ChessPiece,
+    pub color: PlayerColor,
+    pub last_moved: Instant,
+    pub cooldown: Duration,
+    /// set on a pawn that just stepped two squares forward. until this deadline passes an enemy
+    /// pawn beside it can capture it en passant.
+    pub en_passant_until: Option<Instant>,
+    /// false until the piece first moves. kings and rooks need this to castle.
+    pub has_moved: bool,
+}
+
+impl BoardPiece {
+    /// a piece that has never moved and is ready to go.
+    pub fn new(piece: ChessPiece, color: PlayerColor, now: Instant) -> Self {
+        Self {
+            piece,
+            color,
+            last_moved: now,
+            cooldown: Duration::ZERO,
+            en_passant_until: None,
+            has_moved: false,
+        }
+    }
+
+    /// true while this piece can still be captured en passant.
+    pub fn en_passant_open(&self, now: Instant) -> bool {
+        self.en_passant_until.is_some_and(|until| now < until)
+    }
+}
+
+pub type BoardSquare = Option<BoardPiece>;
+
+#[derive(Debug, Clone)]
+pub struct Board {
+    squares: [[BoardSquare; 8]; 8],
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::starting(Instant::now())
+    }
+}
+
+impl Board {
+    /// the pieces in their starting positions, all ready to move at `now`.
+    pub fn starting(now: Instant) -> Self {
+        let mut room = Board::empty();
+        let white_pieces: Vec<(Location, ChessPiece)> = vec![
+            ((Rank::A, File::One), ChessPiece::R),
+            ((Rank::B, File::One), ChessPiece::N),
+            ((Rank::C, File::One), ChessPiece::B),
+            ((Rank::D, File::One), ChessPiece::Q),
+            ((Rank::E, File::One), ChessPiece::K),
+            ((Rank::F, File::One), ChessPiece::B),
+            ((Rank::G, File::One), ChessPiece::N),
+            ((Rank::H, File::One), ChessPiece::R),
+            ((Rank::A, File::Two), ChessPiece::Pawn),
+            ((Rank::B, File::Two), ChessPiece::Pawn),
+            ((Rank::C, File::Two), ChessPiece::Pawn),
+            ((Rank::D, File::Two), ChessPiece::Pawn),
+            ((Rank::E, File::Two), ChessPiece::Pawn),
+            ((Rank::F, File::Two), ChessPiece::Pawn),
+            ((Rank::G, File::Two), ChessPiece::Pawn),
+            ((Rank::H, File::Two), ChessPiece::Pawn),
+        ];
+        let black_pieces: Vec<(Location, ChessPiece)> = vec![
+            ((Rank::A, File::Eight), ChessPiece::R),
+            ((Rank::B, File::Eight), ChessPiece::N),
+            ((Rank::C, File::Eight), ChessPiece::B),
+            ((Rank::D, File::Eight), ChessPiece::Q),
+            ((Rank::E, File::Eight), ChessPiece::K),
+            ((Rank::F, File::Eight), ChessPiece::B),
+            ((Rank::G, File::Eight), ChessPiece::N),
+            ((Rank::H, File::Eight), ChessPiece::R),
+            ((Rank::A, File::Seven), ChessPiece::Pawn),
+            ((Rank::B, File::Seven), ChessPiece::Pawn),
+            ((Rank::C, File::Seven), ChessPiece::Pawn),
+            ((Rank::D, File::Seven), ChessPiece::Pawn),
+            ((Rank::E, File::Seven), ChessPiece::Pawn),
+            ((Rank::F, File::Seven), ChessPiece::Pawn),
+            ((Rank::G, File::Seven), ChessPiece::Pawn),
+            ((Rank::H, File::Seven), ChessPiece::Pawn),
+        ];
+
+        for (loc, piece) in white_pieces {
+            room[&loc] = Some(BoardPiece::new(piece, PlayerColor::White, now));
+        }
+
+        for (loc, piece) in black_pieces {
+            room[&loc] = Some(BoardPiece::new(piece, PlayerColor::Black, now));
+        }
+
+        room
+    }
+
+    /// a board with no pieces on it. handy for setting up positions by hand.
+    pub fn empty() -> Self {
+        Board {
+            squares: [[None; 8]; 8],
+        }
+    }
+
+    /// looks up a square by its raw board indices.
+    pub fn square_at(&self, rank: usize, file: usize) -> BoardSquare {
+        self.squares[rank][file]
+    }
+
+    pub fn get_coords(&self) -> Vec<((usize, usize), BoardPiece)> {
+        let all_squares = self.squares.iter().enumerate().map(move |(rank, files)| {
+            files
+                .iter()
+                .enumerate()
+                .map(move |(file, squares)| squares.map(|square| ((rank, file), square)))
+        });
+        all_squares.flatten().filter_map(|square| square).collect()
+    }
+
+    /// every piece on the board along with where it stands.
+    pub fn placed_pieces(&self) -> Vec<PlacedPiece> {
+        self.get_coords()
+            .into_iter()
+            .map(|((rank, file), board_piece)| PlacedPiece {
+                pos: (Rank::ALL[rank], File::ALL[file]),
+                piece: board_piece.piece,
+                color: board_piece.color,
+            })
+            .collect()
+    }
+}
+
+impl Index<&Location> for Board {
+    type Output = BoardSquare;
+
+    fn index(&self, index: &Location) -> &Self::Output {
+        let rank: usize = index.0.into();
+        let f: usize = index.1.into();
+        &self.squares[rank][f]
+    }
+}
+
+impl IndexMut<&Location> for Board {
+    fn index_mut(&mut self, index: &Location) -> &mut Self::Output {
+        let rank: usize = index.0.into();
+        let f: usize = index.1.into();
+        &mut self.squares[rank][f]
+    }
+}
@@ -0,0 +1,105 @@
+use super::{GameState, MoveError, MoveOutcome, QueuedMove};
+use crate::Location;
+use std::time::Instant;
+
+/// what became of a move in a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution<S> {
+    /// the move was made.
+    Moved(QueuedMove<S>, MoveOutcome),
+    /// the move was illegal, or stopped being legal once the moves before it were made.
+    Rejected(QueuedMove<S>, MoveError),
+    /// opposing pieces tried to move to the same square, so none of them went anywhere.
+    Bounced {
+        to: Location,
+        moves: Vec<QueuedMove<S>>,
+    },
+    /// two opposing pieces moved onto each other's square and captured each other.
+    Traded(QueuedMove<S>, QueuedMove<S>),
+}
+
+/// applies a batch of moves that were made at the same time, in the order `MoveQueue` handed
+/// them back in. moves only conflict with moves by the other player, and only if both would
+/// have been legal on their own against the board as it was before the batch. the rules are
+/// checked in this order:
+///
+/// 1. **swap**: two pieces moving onto each other's square capture each other and both are
+///    removed from the board. if one of them was a king its owner loses, if both were kings the
+///    game is a draw.
+/// 2. **same destination**: pieces moving to the same square bounce off each other. none of
+///    them move and none of them go on cooldown.
+/// 3. **capture of a departing piece**: a piece moving away from a square escapes a capture
+///    aimed at that square. moves leaving a square another move is heading for are made first,
+///    so the capturing piece lands on an empty square, or is rejected if it can only move there
+///    by capturing, like a pawn moving diagonally.
+///
+/// everything else is made one after the other in batch order.
+pub fn resolve_batch<S: Copy>(
+    game: &mut GameState,
+    batch: Vec<QueuedMove<S>>,
+    now: Instant,
+) -> Vec<Resolution<S>> {
+    let viable: Vec<bool> = batch
+        .iter()
+        .map(|queued| {
+            game.check_move(queued.player, &queued.from, &queued.to, now)
+                .is_ok()
+                && !game.on_cooldown(&queued.from, now)
+        })
+        .collect();
+    let contested =
+        |i: usize, j: usize| viable[i] && viable[j] && batch[i].player != batch[j].player;
+    let mut settled = vec![false; batch.len()];
+    let mut resolutions = Vec::new();
+
+    for i in 0..batch.len() {
+        for j in i + 1..batch.len() {
+            let (a, b) = (batch[i], batch[j]);
+
+            if !settled[i] && !settled[j] && contested(i, j) && a.from == b.to && a.to == b.from {
+                game.trade(&a.from, &b.from);
+                settled[i] = true;
+                settled[j] = true;
+                resolutions.push(Resolution::Traded(a, b));
+            }
+        }
+    }
+
+    for i in 0..batch.len() {
+        if settled[i] || !viable[i] {
+            continue;
+        }
+
+        let clashing: Vec<usize> = (0..batch.len())
+            .filter(|j| !settled[*j] && viable[*j] && batch[*j].to == batch[i].to)
+            .collect();
+
+        if clashing.iter().any(|j| contested(i, *j)) {
+            clashing.iter().for_each(|j| settled[*j] = true);
+            resolutions.push(Resolution::Bounced {
+                to: batch[i].to,
+                moves: clashing.iter().map(|j| batch[*j]).collect(),
+            });
+        }
+    }
+
+    let remaining: Vec<usize> = (0..batch.len()).filter(|i| !settled[*i]).collect();
+    let (departing, arriving): (Vec<usize>, Vec<usize>) = remaining.iter().partition(|i| {
+        remaining
+            .iter()
+            .any(|j| batch[*j].player != batch[**i].player && batch[*j].to == batch[**i].from)
+    });
+
+    for i in departing.into_iter().chain(arriving) {
+        let queued = batch[i];
+
+        resolutions.push(
+            match game.apply_move(queued.player, queued.from, queued.to, queued.promotion, now) {
+                Ok(outcome) => Resolution::Moved(queued, outcome),
+                Err(e) => Resolution::Rejected(queued, e),
+            },
+        );
+    }
+
+    resolutions
+}
@@ -0,0 +1,269 @@
+use super::{BoardPiece, DEFAULT_COOLDOWN};
+use crate::{ChessPiece, CooldownRule, MAX_COOLDOWN, PenaltyFactors, PlayerColor};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// decides how long pieces have to wait between moves. a game owns one and asks it about every
+/// move, so policies are free to keep their own state, like how tired each player is.
+pub trait CooldownPolicy: fmt::Debug + Send + Sync {
+    /// how much longer `piece` has to wait before it can move again, or `None` if it can move
+    /// now.
+    fn blocked(&self, piece: &BoardPiece, now: Instant) -> Option<Duration> {
+        Some((piece.last_moved + piece.cooldown).saturating_duration_since(now))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// called when a move is rejected because `piece` was blocked.
+    fn rejected(&mut self, _piece: &BoardPiece, _now: Instant) {}
+
+    /// called when `piece` moves. returns the cooldown it goes on.
+    fn moved(&mut self, piece: &BoardPiece, now: Instant) -> Duration;
+
+    fn clone_box(&self) -> Box<dyn CooldownPolicy>;
+}
+
+impl Clone for Box<dyn CooldownPolicy> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl CooldownRule {
+    /// a fresh policy that plays by this rule.
+    pub fn policy(&self) -> Box<dyn CooldownPolicy> {
+        match *self {
+            Self::Penalty { base, factors } => Box::new(PenaltyCooldown::new(base, factors)),
+            Self::Flat { cooldown } => Box::new(FlatCooldown(cooldown)),
+            Self::PerPiece {
+                pawn,
+                knight,
+                bishop,
+                rook,
+                queen,
+                king,
+            } => Box::new(PieceCooldowns {
+                pawn,
+                knight,
+                bishop,
+                rook,
+                queen,
+                king,
+            }),
+            Self::Stamina {
+                cooldown,
+                capacity,
+                recovery,
+            } => Box::new(StaminaPool::new(cooldown, capacity, recovery)),
+        }
+    }
+}
+
+/// the original scheme. by default a piece can't move for the first three quarters of its
+/// cooldown, and trying to grows that player's cooldown by a third. moving in the last quarter
+/// is allowed but grows it by a quarter. a player's cooldown never goes back down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PenaltyCooldown {
+    pub factors: PenaltyFactors,
+    white: Duration,
+    black: Duration,
+}
+
+impl PenaltyCooldown {
+    pub fn new(base: Duration, factors: PenaltyFactors) -> Self {
+        Self {
+            factors,
+            white: base,
+            black: base,
+        }
+    }
+
+    /// the cooldown a piece of this color is put on after it moves.
+    pub fn cooldown(&self, color: PlayerColor) -> Duration {
+        match color {
+            PlayerColor::White => self.white,
+            PlayerColor::Black => self.black,
+        }
+    }
+
+    fn cooldown_mut(&mut self, color: PlayerColor) -> &mut Duration {
+        match color {
+            PlayerColor::White => &mut self.white,
+            PlayerColor::Black => &mut self.black,
+        }
+    }
+
+    /// grows the cooldown of `color`'s pieces by `penalty`, up to `MAX_COOLDOWN`. penalties
+    /// compound, so without the cap enough of them would overflow.
+    fn penalise(&mut self, color: PlayerColor, penalty: Duration) {
+        let cooldown = self.cooldown_mut(color);
+
+        if *cooldown < MAX_COOLDOWN {
+            *cooldown = cooldown.saturating_add(penalty).min(MAX_COOLDOWN);
+        }
+    }
+}
+
+impl Default for PenaltyCooldown {
+    fn default() -> Self {
+        Self::new(DEFAULT_COOLDOWN, PenaltyFactors::default())
+    }
+}
+
+impl CooldownPolicy for PenaltyCooldown {
+    fn blocked(&self, piece: &BoardPiece, now: Instant) -> Option<Duration> {
+        Some(
+            self.factors
+                .locked
+                .of(piece.cooldown)
+                .saturating_sub(now.saturating_duration_since(piece.last_moved)),
+        )
+        .filter(|remaining| !remaining.is_zero())
+    }
+
+    fn rejected(&mut self, piece: &BoardPiece, _now: Instant) {
+        let penalty = self.factors.rejected.of(piece.cooldown);
+        self.penalise(piece.color, penalty);
+    }
+
+    fn moved(&mut self, piece: &BoardPiece, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(piece.last_moved);
+
+        if elapsed > self.factors.locked.of(piece.cooldown) && elapsed < piece.cooldown {
+            let penalty = self.factors.early.of(piece.cooldown);
+            self.penalise(piece.color, penalty);
+        }
+
+        self.cooldown(piece.color)
+    }
+
+    fn clone_box(&self) -> Box<dyn CooldownPolicy> {
+        Box::new(*self)
+    }
+}
+
+/// every piece waits the same amount of time after every move, with no penalties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlatCooldown(pub Duration);
+
+impl CooldownPolicy for FlatCooldown {
+    fn moved(&mut self, _piece: &BoardPiece, _now: Instant) -> Duration {
+        self.0
+    }
+
+    fn clone_box(&self) -> Box<dyn CooldownPolicy> {
+        Box::new(*self)
+    }
+}
+
+/// each kind of piece has its own cooldown, so stronger pieces can be made to wait longer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PieceCooldowns {
+    pub pawn: Duration,
+    pub knight: Duration,
+    pub bishop: Duration,
+    pub rook: Duration,
+    pub queen: Duration,
+    pub king: Duration,
+}
+
+impl PieceCooldowns {
+    pub fn get(&self, piece: ChessPiece) -> Duration {
+        match piece {
+            ChessPiece::Pawn => self.pawn,
+            ChessPiece::N => self.knight,
+            ChessPiece::B => self.bishop,
+            ChessPiece::R => self.rook,
+            ChessPiece::Q => self.queen,
+            ChessPiece::K => self.king,
+        }
+    }
+}
+
+impl CooldownPolicy for PieceCooldowns {
+    fn moved(&mut self, piece: &BoardPiece, _now: Instant) -> Duration {
+        self.get(piece.piece)
+    }
+
+    fn clone_box(&self) -> Box<dyn CooldownPolicy> {
+        Box::new(*self)
+    }
+}
+
+/// pieces have a short cooldown of their own, but every move also spends a point from a pool
+/// shared by all of a player's pieces. the pool holds `capacity` points and one comes back
+/// every `recovery`, so a player can burst a few moves and then has to slow down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaminaPool {
+    pub cooldown: Duration,
+    pub capacity: u32,
+    pub recovery: Duration,
+    /// when each player's pool will be full again, if it isn't already.
+    white_full_at: Option<Instant>,
+    black_full_at: Option<Instant>,
+}
+
+impl StaminaPool {
+    pub fn new(cooldown: Duration, capacity: u32, recovery: Duration) -> Self {
+        Self {
+            cooldown,
+            capacity: capacity.max(1),
+            recovery,
+            white_full_at: None,
+            black_full_at: None,
+        }
+    }
+
+    /// how many points `color` has left to spend.
+    pub fn stamina(&self, color: PlayerColor, now: Instant) -> u32 {
+        let missing = self.full_at(color).map_or(0, |full_at| {
+            let left = full_at.saturating_duration_since(now);
+
+            left.as_nanos().div_ceil(self.recovery.as_nanos().max(1)) as u32
+        });
+
+        self.capacity.saturating_sub(missing)
+    }
+
+    fn full_at(&self, color: PlayerColor) -> Option<Instant> {
+        match color {
+            PlayerColor::White => self.white_full_at,
+            PlayerColor::Black => self.black_full_at,
+        }
+    }
+
+    fn full_at_mut(&mut self, color: PlayerColor) -> &mut Option<Instant> {
+        match color {
+            PlayerColor::White => &mut self.white_full_at,
+            PlayerColor::Black => &mut self.black_full_at,
+        }
+    }
+}
+
+impl CooldownPolicy for StaminaPool {
+    fn blocked(&self, piece: &BoardPiece, now: Instant) -> Option<Duration> {
+        let own = (piece.last_moved + piece.cooldown).saturating_duration_since(now);
+        // the pool has a point to spend once it's no more than `capacity - 1` points short.
+        let tired = self
+            .full_at(piece.color)
+            .and_then(|full_at| full_at.checked_sub(self.recovery * (self.capacity - 1)))
+            .map_or(Duration::ZERO, |ready_at| {
+                ready_at.saturating_duration_since(now)
+            });
+
+        Some(own.max(tired)).filter(|remaining| !remaining.is_zero())
+    }
+
+    fn moved(&mut self, piece: &BoardPiece, now: Instant) -> Duration {
+        let recovery = self.recovery;
+        let full_at = self.full_at_mut(piece.color);
+        *full_at = Some(full_at.filter(|full_at| *full_at > now).unwrap_or(now) + recovery);
+
+        self.cooldown
+    }
+
+    fn clone_box(&self) -> Box<dyn CooldownPolicy> {
+        Box::new(*self)
+    }
+}
@@ -0,0 +1,85 @@
+use super::{BoardPiece, MOVE_SETTLE};
+use crate::{ChessPiece, File, Location, MoveDelta, PlayerColor, Rank};
+use std::time::{Duration, Instant};
+
+/// a piece that has left its square but not yet landed on its destination. only used when a
+/// game has a `travel_time`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flight {
+    pub player: PlayerColor,
+    /// the piece as it was when it took off.
+    pub piece: BoardPiece,
+    pub from: Location,
+    pub to: Location,
+    pub promotion: Option<ChessPiece>,
+    pub castle: Option<(Location, Location)>,
+    /// true if the piece is a pawn taking another en passant.
+    pub en_passant: bool,
+    /// the cooldown the piece goes on once it lands.
+    pub cooldown: Duration,
+    pub departed: Instant,
+    pub arrives: Instant,
+}
+
+impl Flight {
+    /// the squares the piece crosses between `from` and `to`, in order. knights jump so they
+    /// don't cross any.
+    pub fn path(&self) -> Vec<Location> {
+        let delta = MoveDelta::new(&self.from, &self.to);
+
+        if delta.is_knight_jump() {
+            return Vec::new();
+        }
+
+        let (rank_step, file_step) = delta.step();
+        let (rank, file): (usize, usize) = (self.from.0.into(), self.from.1.into());
+
+        (1..delta.distance())
+            .map(|i| {
+                (
+                    Rank::ALL[(rank as i8 + rank_step * i) as usize],
+                    File::ALL[(file as i8 + file_step * i) as usize],
+                )
+            })
+            .collect()
+    }
+
+    /// when the piece passes over `square`, if it's on its path.
+    pub fn passes(&self, square: &Location) -> Option<Instant> {
+        let path = self.path();
+        let i = path.iter().position(|crossed| crossed == square)?;
+        let steps = path.len() as u32 + 1;
+
+        Some(self.departed + (self.arrives - self.departed) / steps * (i as u32 + 1))
+    }
+
+    /// when the piece's cooldown is over once it has landed.
+    pub fn ready_at(&self) -> Instant {
+        self.arrives + MOVE_SETTLE + self.cooldown
+    }
+
+    /// true if this flight starts or ends on `square`, or a castling rook still has to move
+    /// from or to it once the king lands.
+    pub fn reserves(&self, square: &Location) -> bool {
+        self.from == *square
+            || self.to == *square
+            || self
+                .castle
+                .is_some_and(|(rook_from, rook_to)| rook_from == *square || rook_to == *square)
+    }
+}
+
+/// a flight that landed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Landing {
+    pub flight: Flight,
+    /// the enemy piece that was taken on landing, if any.
+    pub captured: Option<ChessPiece>,
+    /// where the captured piece was taken from. this is the destination except for en passant.
+    pub captured_at: Option<Location>,
+    /// the castling rook's move if it was still there to make it.
+    pub castle: Option<(Location, Location)>,
+    /// enemy pieces that were shot down because this piece landed in their path before they
+    /// got past.
+    pub intercepted: Vec<Flight>,
+}
@@ -0,0 +1,615 @@
+use super::{
+    Board, BoardPiece, DEFAULT_EN_PASSANT_WINDOW, MOVE_SETTLE, MoveError, MoveOutcome,
+    cooldown::{CooldownPolicy, PenaltyCooldown},
+    flight::{Flight, Landing},
+};
+use crate::{ChessPiece, File, Location, MoveDelta, PenaltyFactors, PlayerColor, Rank, ReadyStage};
+use std::time::{Duration, Instant};
+
+/// everything needed to referee a single game.
+#[derive(Debug, Clone)]
+pub struct GameState {
+    pub board: Board,
+    /// how long after a pawn's double step it can still be captured en passant.
+    pub en_passant_window: Duration,
+    /// how long a piece takes to cross one square. when set, moving pieces spend time in the air
+    /// and only land once `land_flights` is called after they arrive. when unset pieces move
+    /// instantly.
+    pub travel_time: Option<Duration>,
+    /// pieces that are still travelling to their destination.
+    flights: Vec<Flight>,
+    /// decides how long pieces wait between moves.
+    cooldown_policy: Box<dyn CooldownPolicy>,
+    /// set once a king has been captured, after which no more moves are accepted.
+    winner: Option<PlayerColor>,
+    /// set when both kings are captured at once, which also ends the game.
+    drawn: bool,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::with_policy(Board::default(), Box::<PenaltyCooldown>::default())
+    }
+}
+
+impl GameState {
+    /// a game using the original penalty cooldowns, starting every player on `cooldown`.
+    pub fn new(board: Board, cooldown: Duration) -> Self {
+        Self::with_policy(
+            board,
+            Box::new(PenaltyCooldown::new(cooldown, PenaltyFactors::default())),
+        )
+    }
+
+    pub fn with_policy(board: Board, cooldown_policy: Box<dyn CooldownPolicy>) -> Self {
+        Self {
+            board,
+            en_passant_window: DEFAULT_EN_PASSANT_WINDOW,
+            travel_time: None,
+            flights: Vec::new(),
+            cooldown_policy,
+            winner: None,
+            drawn: false,
+        }
+    }
+
+    /// the player that captured the other's king, if the game is over.
+    pub fn winner(&self) -> Option<PlayerColor> {
+        self.winner
+    }
+
+    /// true if the game ended in a draw, either with both kings captured at the same time or
+    /// by `declare_draw`.
+    pub fn is_drawn(&self) -> bool {
+        self.drawn
+    }
+
+    /// ends a game nobody has won yet as a draw, like when it runs out of time.
+    pub fn declare_draw(&mut self) {
+        if self.winner.is_none() {
+            self.drawn = true;
+        }
+    }
+
+    /// when each piece that is still cooling down at `now` will be ready, counting the penalty
+    /// window at the end of its cooldown.
+    pub fn cooldowns(&self, now: Instant) -> Vec<(Location, Instant)> {
+        self.board
+            .get_coords()
+            .into_iter()
+            .filter_map(|((rank, file), piece)| {
+                let ready_at = piece.last_moved + piece.cooldown;
+
+                (ready_at > now).then_some(((Rank::ALL[rank], File::ALL[file]), ready_at))
+            })
+            .collect()
+    }
+
+    /// the pieces that came off cooldown after `since`, up to and including `now`. a piece that
+    /// got through both its lock and its penalty window in that time is only reported as
+    /// `ReadyStage::Ready`.
+    pub fn readied(&self, since: Instant, now: Instant) -> Vec<(Location, ReadyStage)> {
+        self.board
+            .get_coords()
+            .into_iter()
+            .filter_map(|((rank, file), piece)| {
+                let ready_at = piece.last_moved + piece.cooldown;
+                let stage = if since < ready_at && ready_at <= now {
+                    ReadyStage::Ready
+                } else if self.cooldown_policy.blocked(&piece, since).is_some()
+                    && self.cooldown_policy.blocked(&piece, now).is_none()
+                {
+                    ReadyStage::Unlocked
+                } else {
+                    return None;
+                };
+
+                Some(((Rank::ALL[rank], File::ALL[file]), stage))
+            })
+            .collect()
+    }
+
+    /// the pieces that are still travelling to their destination.
+    pub fn flights(&self) -> &[Flight] {
+        &self.flights
+    }
+
+    /// validates and, if legal, makes a move for `player`. the game's `CooldownPolicy` decides
+    /// whether the piece has waited long enough and what cooldown it goes on afterwards.
+    ///
+    /// a pawn reaching the far side of the board is promoted to `promotion`, or to a queen when
+    /// no piece was picked. a pawn that steps two squares can be captured en passant until
+    /// `en_passant_window` has passed, since there are no turns to define "right after" by.
+    ///
+    /// castling is a two square king move towards a rook, neither of which have moved yet, with
+    /// nothing standing between them. without turns there is no such thing as check, so the king
+    /// is free to castle out of, through or into an attacked square. both pieces go on cooldown.
+    ///
+    /// capturing the enemy king wins the game.
+    ///
+    /// when the game has a `travel_time` the piece takes off instead of moving straight away,
+    /// and everything that happens on arrival is left to `land_flights`. while it travels
+    /// neither its start nor its destination square can be moved onto, nor the castling rook's
+    /// squares when the king castles.
+    pub fn apply_move(
+        &mut self,
+        player: PlayerColor,
+        from: Location,
+        to: Location,
+        promotion: Option<ChessPiece>,
+        now: Instant,
+    ) -> Result<MoveOutcome, MoveError> {
+        if let Some(winner) = self.winner {
+            return Err(MoveError::GameOver { winner });
+        } else if self.drawn {
+            return Err(MoveError::GameDrawn);
+        }
+
+        self.check_move(player, &from, &to, now)?;
+
+        let Some(moving) = self.board[&from] else {
+            return Err(MoveError::NoPieceAtSource(from));
+        };
+        let piece = moving.piece;
+
+        let promotion = self.promotion_for(piece, player, &to, promotion)?;
+        let castle = self.castle_rook_move(&from, &to);
+
+        if let Some(remaining) = self.cooldown_policy.blocked(&moving, now) {
+            self.cooldown_policy.rejected(&moving, now);
+
+            return Err(MoveError::OnCooldown { remaining });
+        }
+
+        let cooldown = self.cooldown_policy.moved(&moving, now);
+
+        if let Some(per_square) = self.travel_time {
+            let distance = MoveDelta::new(&from, &to).distance() as u32;
+            let en_passant = self.is_en_passant(piece, &from, &to, now);
+            let arrives = now + per_square * distance;
+
+            if let Some(moving) = self.board[&from].take() {
+                self.flights.push(Flight {
+                    player,
+                    piece: moving,
+                    from,
+                    to,
+                    promotion,
+                    castle,
+                    en_passant,
+                    cooldown,
+                    departed: now,
+                    arrives,
+                });
+            }
+
+            return Ok(MoveOutcome {
+                player,
+                piece,
+                from,
+                to,
+                captured: None,
+                captured_at: None,
+                promotion: None,
+                castle: None,
+                cooldown,
+                ready_at: arrives + MOVE_SETTLE + cooldown,
+                arrives: Some(arrives),
+            });
+        }
+
+        // check for capture.
+        let captured_at = if self.is_en_passant(piece, &from, &to, now) {
+            Some(Self::passed_square(&from, &to))
+        } else {
+            Some(to).filter(|to| self.board[to].is_some_and(|target| target.color != player))
+        };
+        let captured =
+            captured_at.and_then(|square| self.board[&square].map(|target| target.piece));
+
+        if let Some(square) = captured_at {
+            self.board[&square] = None;
+        }
+
+        if captured == Some(ChessPiece::K) {
+            self.winner = Some(player);
+        }
+
+        // move peice
+        self.make_move(&from, &to, cooldown, now);
+
+        if let Some((rook_from, rook_to)) = castle {
+            self.make_move(&rook_from, &rook_to, cooldown, now);
+        }
+
+        if let Some(moved) = self.board[&to].as_mut() {
+            if let Some(promoted) = promotion {
+                moved.piece = promoted;
+            }
+
+            if piece == ChessPiece::Pawn && MoveDelta::new(&from, &to).file.abs() == 2 {
+                moved.en_passant_until = Some(now + self.en_passant_window);
+            }
+        }
+
+        Ok(MoveOutcome {
+            player,
+            piece,
+            from,
+            to,
+            captured,
+            captured_at,
+            promotion,
+            castle,
+            cooldown,
+            ready_at: now + MOVE_SETTLE + cooldown,
+            arrives: None,
+        })
+    }
+
+    /// lands every piece that has arrived by `now`, earliest first. a piece lands like it would
+    /// have moved without travel time: it takes whatever enemy piece is still on its
+    /// destination, gets promoted, brings its rook along when castling and goes on cooldown.
+    ///
+    /// a piece landing on a square an enemy piece is still to fly over intercepts it, taking it
+    /// out of the air and off the board. friendly pieces don't intercept each other and knights
+    /// jump so they can't be intercepted.
+    pub fn land_flights(&mut self, now: Instant) -> Vec<Landing> {
+        let mut landings = Vec::new();
+
+        while let Some(i) = self
+            .flights
+            .iter()
+            .enumerate()
+            .filter(|(_, flight)| flight.arrives <= now)
+            .min_by_key(|(_, flight)| flight.arrives)
+            .map(|(i, _)| i)
+        {
+            let flight = self.flights.remove(i);
+            let (from, to) = (flight.from, flight.to);
+            let captured_at = if flight.en_passant {
+                Some(Self::passed_square(&from, &to))
+            } else {
+                Some(to)
+            }
+            .filter(|square| {
+                self.board[square].is_some_and(|target| target.color != flight.player)
+            });
+            let captured = captured_at.and_then(|square| self.board[&square].take());
+
+            if captured.is_some_and(|captured| captured.piece == ChessPiece::K) {
+                self.winner.get_or_insert(flight.player);
+            }
+
+            let double_step = flight.piece.piece == ChessPiece::Pawn
+                && MoveDelta::new(&from, &to).file.abs() == 2;
+            self.board[&to] = Some(BoardPiece {
+                piece: flight.promotion.unwrap_or(flight.piece.piece),
+                last_moved: flight.arrives + MOVE_SETTLE,
+                cooldown: flight.cooldown,
+                en_passant_until: double_step.then_some(flight.arrives + self.en_passant_window),
+                has_moved: true,
+                ..flight.piece
+            });
+
+            // the rook's squares are reserved, but the rook can still have moved away. it never
+            // lands on top of anything.
+            let castle = flight.castle.filter(|(rook_from, rook_to)| {
+                self.board[rook_to].is_none()
+                    && self.board[rook_from].is_some_and(|rook| {
+                        rook.piece == ChessPiece::R
+                            && rook.color == flight.player
+                            && !rook.has_moved
+                    })
+            });
+
+            if let Some((rook_from, rook_to)) = castle {
+                self.make_move(&rook_from, &rook_to, flight.cooldown, flight.arrives);
+            }
+
+            let (intercepted, flying): (Vec<Flight>, Vec<Flight>) =
+                self.flights.drain(..).partition(|other| {
+                    other.player != flight.player
+                        && other
+                            .passes(&to)
+                            .is_some_and(|passes| passes > flight.arrives)
+                });
+            self.flights = flying;
+
+            if intercepted
+                .iter()
+                .any(|other| other.piece.piece == ChessPiece::K)
+            {
+                self.winner.get_or_insert(flight.player);
+            }
+
+            landings.push(Landing {
+                flight,
+                captured: captured.map(|captured| captured.piece),
+                captured_at,
+                castle,
+                intercepted,
+            });
+        }
+
+        landings
+    }
+
+    /// works out what a piece moving to `to` gets promoted to, if anything.
+    fn promotion_for(
+        &self,
+        piece: ChessPiece,
+        player: PlayerColor,
+        to: &Location,
+        requested: Option<ChessPiece>,
+    ) -> Result<Option<ChessPiece>, MoveError> {
+        let last_file = match player {
+            PlayerColor::White => 7,
+            PlayerColor::Black => 0,
+        };
+        let to_file: usize = to.1.into();
+
+        if piece != ChessPiece::Pawn || to_file != last_file {
+            return match requested {
+                Some(requested) => Err(MoveError::InvalidPromotion(requested)),
+                None => Ok(None),
+            };
+        }
+
+        match requested.unwrap_or(ChessPiece::Q) {
+            promoted @ (ChessPiece::Q | ChessPiece::R | ChessPiece::B | ChessPiece::N) => {
+                Ok(Some(promoted))
+            }
+            promoted => Err(MoveError::InvalidPromotion(promoted)),
+        }
+    }
+
+    /// checks that the move is legal for the piece on `from`, ignoring cooldowns. does not
+    /// change any state. `now` is only used to see which en passant windows are still open.
+    pub fn check_move(
+        &self,
+        player: PlayerColor,
+        from: &Location,
+        to: &Location,
+        now: Instant,
+    ) -> Result<(), MoveError> {
+        let Some(BoardPiece {
+            piece,
+            color: moving_peice_color,
+            ..
+        }) = self.board[from]
+        else {
+            return Err(MoveError::NoPieceAtSource(*from));
+        };
+
+        if moving_peice_color != player {
+            return Err(MoveError::NotYourPiece);
+        }
+
+        if self.self_capture(player, to) {
+            return Err(MoveError::SelfCapture);
+        }
+
+        if self.flights.iter().any(|flight| flight.reserves(to)) {
+            return Err(MoveError::Reserved { square: *to });
+        }
+
+        // calculate a vector of movement for the peice and see if its valid.
+        self.validated_move_vec(piece, from, to, moving_peice_color, now)
+    }
+
+    /// every square the piece on `from` could legally move to, ignoring cooldowns.
+    pub fn legal_destinations(&self, from: &Location, now: Instant) -> Vec<Location> {
+        let Some(BoardPiece { color, .. }) = self.board[from] else {
+            return Vec::new();
+        };
+
+        Rank::ALL
+            .into_iter()
+            .flat_map(|rank| File::ALL.into_iter().map(move |file| (rank, file)))
+            .filter(|to| self.check_move(color, from, to, now).is_ok())
+            .collect()
+    }
+
+    /// true if moving the piece on `location` right now would be rejected because it's still on
+    /// cooldown. moving during a penalty window is allowed so doesn't count.
+    pub fn on_cooldown(&self, location: &Location, now: Instant) -> bool {
+        self.board[location]
+            .is_some_and(|piece| self.cooldown_policy.blocked(&piece, now).is_some())
+    }
+
+    /// removes the pieces on `a` and `b`, which moved onto each other's square at the same time
+    /// and captured each other. returns the pieces that were taken. losing a king loses the
+    /// game, losing both kings is a draw.
+    pub fn trade(
+        &mut self,
+        a: &Location,
+        b: &Location,
+    ) -> (Option<ChessPiece>, Option<ChessPiece>) {
+        let (piece_a, piece_b) = (self.board[a].take(), self.board[b].take());
+        let lost_king = |piece: Option<BoardPiece>| {
+            piece
+                .filter(|piece| piece.piece == ChessPiece::K)
+                .map(|king| king.color)
+        };
+
+        match (lost_king(piece_a), lost_king(piece_b)) {
+            (Some(_), Some(_)) => self.drawn = true,
+            (Some(loser), None) | (None, Some(loser)) => self.winner = Some(loser.opponent()),
+            (None, None) => {}
+        }
+
+        (
+            piece_a.map(|piece| piece.piece),
+            piece_b.map(|piece| piece.piece),
+        )
+    }
+
+    /// true if the piece on `location` has finished its cooldown (including the penalty window).
+    pub fn is_ready(&self, location: &Location, now: Instant) -> bool {
+        self.board[location]
+            .is_some_and(|piece| now.saturating_duration_since(piece.last_moved) >= piece.cooldown)
+    }
+
+    fn validated_move_vec(
+        &self,
+        piece: ChessPiece,
+        from: &Location,
+        to: &Location,
+        moving_peice_color: PlayerColor,
+        now: Instant,
+    ) -> Result<(), MoveError> {
+        let delta = MoveDelta::new(from, to);
+
+        if delta.is_null() {
+            return Err(MoveError::NullMove);
+        }
+
+        let legal = match piece {
+            ChessPiece::K => delta.distance() == 1 || self.castle_rook_move(from, to).is_some(),
+            ChessPiece::N => delta.is_knight_jump(),
+            ChessPiece::Q => delta.is_straight() || delta.is_diagonal(),
+            ChessPiece::B => delta.is_diagonal(),
+            ChessPiece::R => delta.is_straight(),
+            ChessPiece::Pawn => {
+                self.validate_pawn_move(delta, from, to, moving_peice_color)
+                    || self.is_en_passant(piece, from, to, now)
+            }
+        };
+
+        if !legal {
+            return Err(MoveError::IllegalPattern(piece));
+        }
+
+        if piece != ChessPiece::N {
+            self.piece_in_way(delta, from)?;
+        }
+
+        Ok(())
+    }
+
+    /// walks the squares strictly between `from` and the destination and fails on the first
+    /// occupied one. the destination itself is handled by the capture checks.
+    fn piece_in_way(&self, delta: MoveDelta, from: &Location) -> Result<(), MoveError> {
+        let (rank_step, file_step) = delta.step();
+        let rank: usize = from.0.into();
+        let file: usize = from.1.into();
+
+        for i in 1..delta.distance() {
+            let rank = (rank as i8 + rank_step * i) as usize;
+            let file = (file as i8 + file_step * i) as usize;
+
+            if self.board.square_at(rank, file).is_some() {
+                return Err(MoveError::Blocked {
+                    by: (Rank::ALL[rank], File::ALL[file]),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_pawn_move(
+        &self,
+        delta: MoveDelta,
+        from: &Location,
+        to: &Location,
+        moving_peice_color: PlayerColor,
+    ) -> bool {
+        let (forward, home_file) = match moving_peice_color {
+            PlayerColor::White => (1, 1),
+            PlayerColor::Black => (-1, 6),
+        };
+        let from_file: usize = from.1.into();
+
+        if delta.rank == 0 && delta.file == 2 * forward {
+            return from_file == home_file && self.board[to].is_none();
+        }
+
+        delta.file == forward
+            && match delta.rank.abs() {
+                0 => self.board[to].is_none(),
+                1 => self.board[to].is_some_and(|target| moving_peice_color != target.color),
+                _ => false,
+            }
+    }
+
+    /// true if `from` -> `to` is a pawn taking an enemy pawn en passant. the enemy pawn has to be
+    /// beside `from`, behind the empty square `to`, and still inside its en passant window.
+    fn is_en_passant(
+        &self,
+        piece: ChessPiece,
+        from: &Location,
+        to: &Location,
+        now: Instant,
+    ) -> bool {
+        let Some(BoardPiece { color, .. }) = self.board[from] else {
+            return false;
+        };
+        let forward = match color {
+            PlayerColor::White => 1,
+            PlayerColor::Black => -1,
+        };
+        let delta = MoveDelta::new(from, to);
+
+        piece == ChessPiece::Pawn
+            && delta.file == forward
+            && delta.rank.abs() == 1
+            && self.board[to].is_none()
+            && self.board[&Self::passed_square(from, to)].is_some_and(|target| {
+                target.piece == ChessPiece::Pawn
+                    && target.color != color
+                    && target.en_passant_open(now)
+            })
+    }
+
+    /// the rook's start and end squares if `from` -> `to` is a king castling.
+    fn castle_rook_move(&self, from: &Location, to: &Location) -> Option<(Location, Location)> {
+        let king = self.board[from]?;
+        let delta = MoveDelta::new(from, to);
+
+        if king.piece != ChessPiece::K || king.has_moved || delta.file != 0 || delta.rank.abs() != 2
+        {
+            return None;
+        }
+
+        let (rook_rank, rook_to_rank) = if delta.rank > 0 {
+            (Rank::H, Rank::F)
+        } else {
+            (Rank::A, Rank::D)
+        };
+        let rook_from = (rook_rank, from.1);
+        let rook = self.board[&rook_from]?;
+
+        if rook.piece != ChessPiece::R || rook.color != king.color || rook.has_moved {
+            return None;
+        }
+
+        // every square between the king and the rook has to be empty.
+        let to_rook = MoveDelta::new(from, &rook_from);
+        self.piece_in_way(to_rook, from).ok()?;
+
+        Some((rook_from, (rook_to_rank, from.1)))
+    }
+
+    /// the square a pawn capturing en passant from `from` to `to` removes the enemy pawn from.
+    fn passed_square(from: &Location, to: &Location) -> Location {
+        (to.0, from.1)
+    }
+
+    fn make_move(&mut self, from: &Location, to: &Location, cooldown: Duration, now: Instant) {
+        let piece = self.board[from].map(|piece| BoardPiece {
+            last_moved: now + MOVE_SETTLE,
+            cooldown,
+            en_passant_until: None,
+            has_moved: true,
+            ..piece
+        });
+        self.board[to] = piece;
+        self.board[from] = None;
+    }
+
+    fn self_capture(&self, player_color: PlayerColor, to: &Location) -> bool {
+        self.board[to].is_some_and(|piece| piece.color == player_color)
+    }
+}
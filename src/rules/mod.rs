@@ -0,0 +1,139 @@
+//! the rules of real-time chess. nothing in here knows about bevy or the network so it can be
+//! shared by the server, the client and anything else that needs to reason about a game.
+
+use crate::{ChessPiece, Location, PlayerColor};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+pub mod board;
+pub mod clock;
+pub mod conflict;
+pub mod cooldown;
+pub mod flight;
+pub mod game_state;
+pub mod move_queue;
+pub mod premove;
+
+pub use board::{Board, BoardPiece, BoardSquare};
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use conflict::{Resolution, resolve_batch};
+pub use cooldown::{CooldownPolicy, FlatCooldown, PenaltyCooldown, PieceCooldowns, StaminaPool};
+pub use flight::{Flight, Landing};
+pub use game_state::GameState;
+pub use move_queue::{MoveQueue, QueuedMove};
+pub use premove::PremoveQueue;
+
+/// the cooldown every player starts a game with.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(5);
+/// how long after a move the moved piece's cooldown starts.
+pub const MOVE_SETTLE: Duration = Duration::from_millis(100);
+/// how long a pawn that stepped two squares stays open to en passant capture.
+pub const DEFAULT_EN_PASSANT_WINDOW: Duration = Duration::from_millis(1500);
+
+/// a move that was accepted and applied to the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveOutcome {
+    pub player: PlayerColor,
+    pub piece: ChessPiece,
+    pub from: Location,
+    pub to: Location,
+    /// the enemy piece that was standing on `to`, if any.
+    pub captured: Option<ChessPiece>,
+    /// the square the captured piece was removed from. this is `to` except for en passant.
+    pub captured_at: Option<Location>,
+    /// what the piece turned into if it was a pawn that reached the far side of the board.
+    pub promotion: Option<ChessPiece>,
+    /// where the rook moved from and to when this move was the king castling.
+    pub castle: Option<(Location, Location)>,
+    /// how long the moved piece is now on cooldown for.
+    pub cooldown: Duration,
+    /// when the moved piece's cooldown, penalty window included, is over.
+    pub ready_at: Instant,
+    /// when the piece lands if the game has travel time. captures, the castling rook's move
+    /// and everything else that happens on arrival are reported by `land_flights` instead.
+    pub arrives: Option<Instant>,
+}
+
+/// why a move was rejected.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MoveError {
+    NoPieceAtSource(Location),
+    NotYourPiece,
+    SelfCapture,
+    NullMove,
+    Blocked {
+        by: Location,
+    },
+    IllegalPattern(ChessPiece),
+    InvalidPromotion(ChessPiece),
+    OnCooldown {
+        remaining: Duration,
+    },
+    GameOver {
+        winner: PlayerColor,
+    },
+    GameDrawn,
+    /// a travelling piece is leaving from or heading for that square.
+    Reserved {
+        square: Location,
+    },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoPieceAtSource(from) => {
+                write!(f, "there is no peice to move at possision {from:?}")
+            }
+            Self::NotYourPiece => write!(f, "you can only move your own peices."),
+            Self::SelfCapture => write!(
+                f,
+                "you can't move a peice to a square ocupied by one of your own peice."
+            ),
+            Self::NullMove => write!(f, "a peice has to move at least one square."),
+            Self::Blocked { by } => {
+                write!(f, "there was a piece in the way of that movement at {by:?}")
+            }
+            Self::IllegalPattern(piece) => match piece {
+                ChessPiece::K => write!(f, "the king can only move one square at a time."),
+                ChessPiece::Q => write!(
+                    f,
+                    "the queen must move diaganoly, vertically, or horzonatally."
+                ),
+                ChessPiece::B => write!(f, "the bishop can only move diagonally."),
+                ChessPiece::N => write!(
+                    f,
+                    "the knight must move two squares one way and one square the other."
+                ),
+                ChessPiece::R => write!(f, "the rook can only move vertically or horizontally."),
+                ChessPiece::Pawn => write!(
+                    f,
+                    "pawns move one square forward, two from their starting square, and only \
+                     diaganoly when capturing a piece."
+                ),
+            },
+            Self::InvalidPromotion(piece) => write!(
+                f,
+                "can't promote to {piece:?}. only pawns reaching the far side of the board can \
+                 be promoted, and only to a queen, rook, bishop or knight."
+            ),
+            Self::OnCooldown { remaining } => {
+                write!(f, "that peice is on cooldown for another {remaining:?}.")
+            }
+            Self::GameOver { winner } => {
+                write!(f, "the game is over. {winner:?} captured the king.")
+            }
+            Self::GameDrawn => write!(f, "the game is over. it ended in a draw."),
+            Self::Reserved { square } => write!(
+                f,
+                "a peice is travelling to or from {square:?}, nothing else can move there until \
+                 it lands."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
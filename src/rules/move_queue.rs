@@ -0,0 +1,122 @@
+use crate::{ChessPiece, Location, PlayerColor};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// how long moves are held before being resolved together.
+pub const DEFAULT_INPUT_WINDOW: Duration = Duration::from_millis(125);
+
+/// a move waiting in a `MoveQueue`. `sender` is whatever the caller uses to tell who sent it,
+/// the server uses the client id so it knows who to send rejections to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueuedMove<S> {
+    pub sender: S,
+    pub player: PlayerColor,
+    pub from: Location,
+    pub to: Location,
+    pub promotion: Option<ChessPiece>,
+    pub received: Instant,
+}
+
+/// holds incoming moves for `window` and hands them back as a batch, so a player with a lower
+/// ping doesn't automatically win every contested exchange.
+///
+/// the window opens when a move arrives at an empty queue and every move received before it
+/// closes goes in the same batch. a batch is ordered like so:
+///
+/// 1. each player's own moves stay in the order they were received.
+/// 2. the two players' moves are interleaved, one from each in turn, for as long as both have
+///    moves left. after that the rest of the remaining player's moves follow.
+/// 3. which player goes first alternates from one contested batch to the next, starting with
+///    white. a batch with moves from only one player doesn't count as contested.
+///
+/// arrival order between players within a window never decides anything.
+#[derive(Debug, Clone)]
+pub struct MoveQueue<S> {
+    pub window: Duration,
+    pending: Vec<QueuedMove<S>>,
+    opened_at: Option<Instant>,
+    /// the player whose move goes first in the next contested batch.
+    first_pick: PlayerColor,
+}
+
+impl<S> Default for MoveQueue<S> {
+    fn default() -> Self {
+        Self::new(DEFAULT_INPUT_WINDOW)
+    }
+}
+
+impl<S> MoveQueue<S> {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Vec::new(),
+            opened_at: None,
+            first_pick: PlayerColor::White,
+        }
+    }
+
+    pub fn push(&mut self, queued: QueuedMove<S>) {
+        if self.opened_at.is_none() {
+            self.opened_at = Some(queued.received);
+        }
+
+        self.pending.push(queued);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// the moves waiting in the queue, in the order they arrived.
+    pub fn pending(&self) -> &[QueuedMove<S>] {
+        &self.pending
+    }
+
+    /// drops every move waiting in the queue.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.opened_at = None;
+    }
+
+    /// takes the current batch out of the queue, in the order it should be applied, once its
+    /// window has closed. returns nothing while the window is still open.
+    pub fn take_batch(&mut self, now: Instant) -> Vec<QueuedMove<S>> {
+        let Some(opened_at) = self.opened_at else {
+            return Vec::new();
+        };
+
+        if now.saturating_duration_since(opened_at) < self.window {
+            return Vec::new();
+        }
+
+        self.opened_at = None;
+        let mut batch: Vec<_> = self.pending.drain(..).collect();
+        batch.sort_by_key(|queued| queued.received);
+
+        let (mut white, mut black): (VecDeque<_>, VecDeque<_>) = batch
+            .into_iter()
+            .partition(|queued| queued.player == PlayerColor::White);
+        let contested = !white.is_empty() && !black.is_empty();
+        let (mut first, mut second) = match self.first_pick {
+            PlayerColor::White => (&mut white, &mut black),
+            PlayerColor::Black => (&mut black, &mut white),
+        };
+
+        let mut ordered = Vec::with_capacity(first.len() + second.len());
+
+        while let Some(queued) = first.pop_front() {
+            ordered.push(queued);
+            std::mem::swap(&mut first, &mut second);
+        }
+
+        ordered.extend(second.drain(..));
+
+        if contested {
+            self.first_pick = self.first_pick.opponent();
+        }
+
+        ordered
+    }
+}
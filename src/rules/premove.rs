@@ -0,0 +1,100 @@
+use super::{GameState, MoveError, QueuedMove};
+use crate::{Location, PlayerColor, Premove};
+use std::time::Instant;
+
+/// a premove that was taken out of the queue without being made, and why.
+pub type DroppedPremove<S> = (QueuedMove<S>, MoveError);
+
+/// moves waiting for their piece to come off cooldown, at most one per piece.
+#[derive(Debug, Clone)]
+pub struct PremoveQueue<S> {
+    queued: Vec<QueuedMove<S>>,
+}
+
+impl<S> Default for PremoveQueue<S> {
+    fn default() -> Self {
+        Self { queued: Vec::new() }
+    }
+}
+
+impl<S: Copy> PremoveQueue<S> {
+    /// queues `premove`, replacing whatever was already queued for the same piece.
+    pub fn queue(&mut self, premove: QueuedMove<S>) {
+        self.queued
+            .retain(|queued| queued.player != premove.player || queued.from != premove.from);
+        self.queued.push(premove);
+    }
+
+    /// cancels the premove `player` queued for the piece on `from`, returning it.
+    pub fn cancel(&mut self, player: PlayerColor, from: &Location) -> Option<QueuedMove<S>> {
+        let i = self
+            .queued
+            .iter()
+            .position(|queued| queued.player == player && queued.from == *from)?;
+
+        Some(self.queued.remove(i))
+    }
+
+    /// the premoves `player` has queued, oldest first.
+    pub fn for_player(&self, player: PlayerColor) -> Vec<Premove> {
+        self.queued
+            .iter()
+            .filter(|queued| queued.player == player)
+            .map(|queued| Premove {
+                from: queued.from,
+                to: queued.to,
+                promotion: queued.promotion,
+            })
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.queued.clear();
+    }
+
+    /// drops every premove `sender` queued, say because they gave up their seat. whoever takes
+    /// the seat next shouldn't inherit them.
+    pub fn drop_sender(&mut self, sender: &S)
+    where
+        S: PartialEq,
+    {
+        self.queued.retain(|queued| queued.sender != *sender);
+    }
+
+    /// takes out the premoves whose piece has landed, is ready, penalty window and all, and can
+    /// still make the move. premoves whose piece was captured, or that stopped being legal by the time the
+    /// piece was ready, say because something moved into its path, are taken out too and handed
+    /// back with the reason.
+    pub fn take_ready(
+        &mut self,
+        game: &GameState,
+        now: Instant,
+    ) -> (Vec<QueuedMove<S>>, Vec<DroppedPremove<S>>) {
+        let mut ready = Vec::new();
+        let mut dropped = Vec::new();
+
+        self.queued.retain(|queued| {
+            let still_there =
+                game.board[&queued.from].is_some_and(|piece| piece.color == queued.player);
+            let landing = game
+                .flights()
+                .iter()
+                .any(|flight| flight.player == queued.player && flight.to == queued.from);
+
+            let waiting = !game.is_ready(&queued.from, now) || game.on_cooldown(&queued.from, now);
+
+            if landing || (still_there && waiting) {
+                return true;
+            }
+
+            match game.check_move(queued.player, &queued.from, &queued.to, now) {
+                Ok(()) => ready.push(*queued),
+                Err(e) => dropped.push((*queued, e)),
+            }
+
+            false
+        });
+
+        (ready, dropped)
+    }
+}
@@ -0,0 +1,187 @@
+use crate::ConfigError;
+use bevy::log::Level;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub mod server;
+
+/// how much gets logged.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn level(self) -> Level {
+        match self {
+            Self::Error => Level::ERROR,
+            Self::Warn => Level::WARN,
+            Self::Info => Level::INFO,
+            Self::Debug => Level::DEBUG,
+            Self::Trace => Level::TRACE,
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => Err(()),
+        }
+    }
+}
+
+/// why the settings couldn't be loaded.
+#[derive(Debug)]
+pub enum SettingsError {
+    /// `--help` was passed. not really an error, but the binary should print its usage and quit.
+    Help,
+    UnknownFlag(String),
+    /// the last flag didn't have a value after it.
+    MissingValue(String),
+    BadValue {
+        flag: String,
+        value: String,
+    },
+    /// the config file couldn't be read.
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// the config file isn't json, or has settings that don't exist.
+    Parse {
+        path: PathBuf,
+        error: serde_json::Error,
+    },
+    /// the default room settings don't make for a game that can be played.
+    BadRoom(ConfigError),
+    /// the server is bound to every interface, so clients need to be told where to find it.
+    NoPublicAddress,
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Help => write!(f, "help requested."),
+            Self::UnknownFlag(flag) => write!(f, "there's no {flag} flag."),
+            Self::MissingValue(flag) => write!(f, "{flag} needs a value."),
+            Self::BadValue { flag, value } => write!(f, "{value:?} isn't a valid --{flag}."),
+            Self::Io { path, error } => write!(f, "couldn't read {}: {error}", path.display()),
+            Self::Parse { path, error } => write!(f, "{} is invalid: {error}", path.display()),
+            Self::BadRoom(e) => write!(f, "the default room settings are invalid: {e}"),
+            Self::NoPublicAddress => write!(
+                f,
+                "the server is bound to every interface, give it a --public address too."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// settings for one of the binaries. they start from `Default`, get replaced by the config
+/// file if `--config` is passed, and then each flag overrides what it sets.
+pub trait Settings: Default + DeserializeOwned {
+    /// applies `--flag value`.
+    fn set(&mut self, flag: &str, value: &str) -> Result<(), SettingsError>;
+
+    /// true if `flag` is turned on just by passing it, without a value.
+    fn is_switch(_flag: &str) -> bool {
+        false
+    }
+
+    /// checks the settings once everything is applied.
+    fn validate(&self) -> Result<(), SettingsError> {
+        Ok(())
+    }
+}
+
+/// parses the value of a flag, blaming the flag if it doesn't parse.
+pub fn parse_flag<T: FromStr>(flag: &str, value: &str) -> Result<T, SettingsError> {
+    value.parse().map_err(|_| SettingsError::BadValue {
+        flag: flag.into(),
+        value: value.into(),
+    })
+}
+
+/// reads a json config file. settings it leaves out keep their defaults.
+pub fn read_config<S: Settings>(path: &Path) -> Result<S, SettingsError> {
+    let text = std::fs::read_to_string(path).map_err(|error| SettingsError::Io {
+        path: path.into(),
+        error,
+    })?;
+
+    serde_json::from_str(&text).map_err(|error| SettingsError::Parse {
+        path: path.into(),
+        error,
+    })
+}
+
+/// loads the settings from the command line, without the binary's name. flags are given as
+/// `--flag value` or `--flag=value`, and switches as just `--flag`.
+pub fn load_settings<S: Settings>(
+    args: impl IntoIterator<Item = String>,
+) -> Result<S, SettingsError> {
+    let mut args = args.into_iter();
+    let mut config = None;
+    let mut flags = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            return Err(SettingsError::Help);
+        }
+
+        let Some(flag) = arg.strip_prefix("--") else {
+            return Err(SettingsError::UnknownFlag(arg));
+        };
+
+        let (flag, value) = match flag.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), value.to_string()),
+            None if S::is_switch(flag) => (flag.to_string(), "true".to_string()),
+            None => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| SettingsError::MissingValue(arg.clone()))?;
+
+                (flag.to_string(), value)
+            }
+        };
+
+        if flag == "config" {
+            config = Some(PathBuf::from(value));
+        } else {
+            flags.push((flag, value));
+        }
+    }
+
+    // the file goes first so the flags can override it no matter where `--config` was passed.
+    let mut settings = match config {
+        Some(path) => read_config(&path)?,
+        None => S::default(),
+    };
+
+    for (flag, value) in &flags {
+        settings.set(flag, value)?;
+    }
+
+    settings.validate()?;
+
+    Ok(settings)
+}
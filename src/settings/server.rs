@@ -0,0 +1,131 @@
+use super::{LogLevel, Settings, SettingsError, parse_flag};
+use crate::{RoomConfig, rules::move_queue::DEFAULT_INPUT_WINDOW};
+use serde::{Deserialize, Serialize};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+pub const USAGE: &str = "\
+usage: rt-chess-server [--config <file>] [--flag <value>]...
+
+flags:
+    --config <file>       read the settings from a json file first. flags override it.
+    --bind <ip>           the address to listen on. (default 127.0.0.1)
+    --public <ip>,...     the addresses clients connect to, if not the bind address.
+    --port <port>         the port to listen on. (default 5000)
+    --max-clients <n>     how many clients can be connected at once. (default 64)
+    --log-level <level>   error, warn, info, debug or trace. (default info)
+    --tick-rate <hz>      how many times a second the server updates. (default 60)
+    --input-window <ms>   how long moves are held so they can be resolved together.
+                          (default 125)
+    --dashboard           open a window showing the network stats of every client. needs a
+                          display and a server built with `--features dashboard`.
+";
+
+/// how the server is set up. rooms started without their own rules get `room`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    pub bind: IpAddr,
+    /// where clients find the server. needed when it's bound to every interface or sits behind
+    /// a NAT, otherwise the bind address is used.
+    pub public: Vec<IpAddr>,
+    pub port: u16,
+    pub max_clients: usize,
+    pub log_level: LogLevel,
+    pub room: RoomConfig,
+    /// how many times a second the server updates when it runs without a window.
+    pub tick_rate: u32,
+    /// how long each room holds incoming moves before resolving them as a batch.
+    pub input_window: Duration,
+    /// opens a window with the renet visualizer instead of running headless.
+    pub dashboard: bool,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            public: Vec::new(),
+            port: 5000,
+            max_clients: 64,
+            log_level: LogLevel::default(),
+            room: RoomConfig::default(),
+            tick_rate: 60,
+            input_window: DEFAULT_INPUT_WINDOW,
+            dashboard: false,
+        }
+    }
+}
+
+impl ServerSettings {
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.bind, self.port)
+    }
+
+    /// how long the server waits between updates when it runs without a window.
+    pub fn tick(&self) -> Duration {
+        Duration::from_secs(1) / self.tick_rate
+    }
+
+    pub fn public_addrs(&self) -> Vec<SocketAddr> {
+        if self.public.is_empty() {
+            return vec![self.bind_addr()];
+        }
+
+        self.public
+            .iter()
+            .map(|ip| SocketAddr::new(*ip, self.port))
+            .collect()
+    }
+}
+
+impl Settings for ServerSettings {
+    fn set(&mut self, flag: &str, value: &str) -> Result<(), SettingsError> {
+        match flag {
+            "bind" => self.bind = parse_flag(flag, value)?,
+            "public" => {
+                self.public = value
+                    .split(',')
+                    .map(|ip| parse_flag(flag, ip.trim()))
+                    .collect::<Result<_, _>>()?
+            }
+            "port" => self.port = parse_flag(flag, value)?,
+            "max-clients" => self.max_clients = parse_flag(flag, value)?,
+            "log-level" => self.log_level = parse_flag(flag, value)?,
+            "tick-rate" => self.tick_rate = parse_flag(flag, value)?,
+            "input-window" => self.input_window = Duration::from_millis(parse_flag(flag, value)?),
+            "dashboard" => self.dashboard = parse_flag(flag, value)?,
+            _ => return Err(SettingsError::UnknownFlag(format!("--{flag}"))),
+        }
+
+        Ok(())
+    }
+
+    fn is_switch(flag: &str) -> bool {
+        flag == "dashboard"
+    }
+
+    fn validate(&self) -> Result<(), SettingsError> {
+        if self.max_clients == 0 {
+            return Err(SettingsError::BadValue {
+                flag: "max-clients".into(),
+                value: "0".into(),
+            });
+        }
+
+        if self.tick_rate == 0 {
+            return Err(SettingsError::BadValue {
+                flag: "tick-rate".into(),
+                value: "0".into(),
+            });
+        }
+
+        if self.bind.is_unspecified() && self.public.is_empty() {
+            return Err(SettingsError::NoPublicAddress);
+        }
+
+        self.room.validate().map_err(SettingsError::BadRoom)
+    }
+}
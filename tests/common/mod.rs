@@ -0,0 +1,20 @@
+use real_time_chess::{
+    ChessPiece, Location, PlayerColor,
+    rules::{Board, BoardPiece, GameState},
+};
+use std::time::{Duration, Instant};
+
+/// a game with only `pieces` on the board, none of which have moved.
+pub fn game_with(
+    pieces: &[(Location, ChessPiece, PlayerColor)],
+    cooldown: Duration,
+    now: Instant,
+) -> GameState {
+    let mut board = Board::empty();
+
+    for (square, piece, color) in pieces {
+        board[square] = Some(BoardPiece::new(*piece, *color, now));
+    }
+
+    GameState::new(board, cooldown)
+}
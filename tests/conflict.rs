@@ -0,0 +1,324 @@
+use real_time_chess::{
+    ChessPiece, File, Location, PlayerColor, Rank,
+    rules::{DEFAULT_COOLDOWN, GameState, MoveError, QueuedMove, Resolution, resolve_batch},
+};
+use std::time::Instant;
+
+mod common;
+
+fn queued(player: PlayerColor, from: Location, to: Location, now: Instant) -> QueuedMove<u8> {
+    QueuedMove {
+        sender: 0,
+        player,
+        from,
+        to,
+        promotion: None,
+        received: now,
+    }
+}
+
+fn piece_at(game: &GameState, square: Location) -> Option<(ChessPiece, PlayerColor)> {
+    game.board[&square].map(|piece| (piece.piece, piece.color))
+}
+
+#[test]
+fn swap_trades_both_pieces() {
+    let now = Instant::now();
+    let (a1, a8) = ((Rank::A, File::One), (Rank::A, File::Eight));
+    let mut game = common::game_with(
+        &[
+            (a1, ChessPiece::R, PlayerColor::White),
+            (a8, ChessPiece::R, PlayerColor::Black),
+        ],
+        DEFAULT_COOLDOWN,
+        now,
+    );
+    let white = queued(PlayerColor::White, a1, a8, now);
+    let black = queued(PlayerColor::Black, a8, a1, now);
+
+    let resolutions = resolve_batch(&mut game, vec![white, black], now);
+
+    assert_eq!(resolutions, vec![Resolution::Traded(white, black)]);
+    assert_eq!(piece_at(&game, a1), None);
+    assert_eq!(piece_at(&game, a8), None);
+    assert_eq!(game.winner(), None);
+}
+
+#[test]
+fn swap_with_a_king_loses_the_game() {
+    let now = Instant::now();
+    let (e1, e2) = ((Rank::E, File::One), (Rank::E, File::Two));
+    let mut game = common::game_with(
+        &[
+            (e1, ChessPiece::K, PlayerColor::White),
+            (e2, ChessPiece::Q, PlayerColor::Black),
+        ],
+        DEFAULT_COOLDOWN,
+        now,
+    );
+
+    resolve_batch(
+        &mut game,
+        vec![
+            queued(PlayerColor::White, e1, e2, now),
+            queued(PlayerColor::Black, e2, e1, now),
+        ],
+        now,
+    );
+
+    assert_eq!(game.winner(), Some(PlayerColor::Black));
+    assert!(!game.is_drawn());
+}
+
+#[test]
+fn swapping_kings_is_a_draw() {
+    let now = Instant::now();
+    let (e4, e5) = ((Rank::E, File::Four), (Rank::E, File::Five));
+    let mut game = common::game_with(
+        &[
+            (e4, ChessPiece::K, PlayerColor::White),
+            (e5, ChessPiece::K, PlayerColor::Black),
+        ],
+        DEFAULT_COOLDOWN,
+        now,
+    );
+
+    resolve_batch(
+        &mut game,
+        vec![
+            queued(PlayerColor::White, e4, e5, now),
+            queued(PlayerColor::Black, e5, e4, now),
+        ],
+        now,
+    );
+
+    assert_eq!(game.winner(), None);
+    assert!(game.is_drawn());
+    assert_eq!(
+        game.apply_move(PlayerColor::White, e4, e5, None, now),
+        Err(MoveError::GameDrawn)
+    );
+}
+
+#[test]
+fn same_destination_bounces() {
+    let now = Instant::now();
+    let (a1, h4, a4) = (
+        (Rank::A, File::One),
+        (Rank::H, File::Four),
+        (Rank::A, File::Four),
+    );
+    let mut game = common::game_with(
+        &[
+            (a1, ChessPiece::R, PlayerColor::White),
+            (h4, ChessPiece::R, PlayerColor::Black),
+        ],
+        DEFAULT_COOLDOWN,
+        now,
+    );
+    let white = queued(PlayerColor::White, a1, a4, now);
+    let black = queued(PlayerColor::Black, h4, a4, now);
+
+    let resolutions = resolve_batch(&mut game, vec![white, black], now);
+
+    assert_eq!(
+        resolutions,
+        vec![Resolution::Bounced {
+            to: a4,
+            moves: vec![white, black],
+        }]
+    );
+    assert_eq!(
+        piece_at(&game, a1),
+        Some((ChessPiece::R, PlayerColor::White))
+    );
+    assert_eq!(
+        piece_at(&game, h4),
+        Some((ChessPiece::R, PlayerColor::Black))
+    );
+    assert_eq!(piece_at(&game, a4), None);
+    assert!(game.is_ready(&a1, now));
+    assert!(game.is_ready(&h4, now));
+}
+
+#[test]
+fn departing_piece_escapes_capture() {
+    let now = Instant::now();
+    let (a1, a5, b7) = (
+        (Rank::A, File::One),
+        (Rank::A, File::Five),
+        (Rank::B, File::Seven),
+    );
+    let mut game = common::game_with(
+        &[
+            (a1, ChessPiece::R, PlayerColor::White),
+            (a5, ChessPiece::N, PlayerColor::Black),
+        ],
+        DEFAULT_COOLDOWN,
+        now,
+    );
+
+    // the capture comes first in the batch but the knight still gets away.
+    let resolutions = resolve_batch(
+        &mut game,
+        vec![
+            queued(PlayerColor::White, a1, a5, now),
+            queued(PlayerColor::Black, a5, b7, now),
+        ],
+        now,
+    );
+
+    let captured: Vec<_> = resolutions
+        .iter()
+        .map(|resolution| match resolution {
+            Resolution::Moved(queued, outcome) => (queued.from, outcome.captured),
+            other => panic!("expected both moves to be made, got {other:?}"),
+        })
+        .collect();
+
+    assert_eq!(captured, vec![(a5, None), (a1, None)]);
+    assert_eq!(
+        piece_at(&game, a5),
+        Some((ChessPiece::R, PlayerColor::White))
+    );
+    assert_eq!(
+        piece_at(&game, b7),
+        Some((ChessPiece::N, PlayerColor::Black))
+    );
+}
+
+#[test]
+fn pawn_capture_of_departing_piece_is_rejected() {
+    let now = Instant::now();
+    let (d4, e5, f7) = (
+        (Rank::D, File::Four),
+        (Rank::E, File::Five),
+        (Rank::F, File::Seven),
+    );
+    let mut game = common::game_with(
+        &[
+            (d4, ChessPiece::Pawn, PlayerColor::White),
+            (e5, ChessPiece::N, PlayerColor::Black),
+        ],
+        DEFAULT_COOLDOWN,
+        now,
+    );
+    let pawn = queued(PlayerColor::White, d4, e5, now);
+
+    let resolutions = resolve_batch(
+        &mut game,
+        vec![pawn, queued(PlayerColor::Black, e5, f7, now)],
+        now,
+    );
+
+    assert_eq!(
+        resolutions.last(),
+        Some(&Resolution::Rejected(
+            pawn,
+            MoveError::IllegalPattern(ChessPiece::Pawn)
+        ))
+    );
+    assert_eq!(
+        piece_at(&game, d4),
+        Some((ChessPiece::Pawn, PlayerColor::White))
+    );
+    assert_eq!(
+        piece_at(&game, f7),
+        Some((ChessPiece::N, PlayerColor::Black))
+    );
+}
+
+#[test]
+fn capture_of_a_piece_that_stays_put_still_captures() {
+    let now = Instant::now();
+    let (a1, a5) = ((Rank::A, File::One), (Rank::A, File::Five));
+    let mut game = common::game_with(
+        &[
+            (a1, ChessPiece::R, PlayerColor::White),
+            (a5, ChessPiece::N, PlayerColor::Black),
+        ],
+        DEFAULT_COOLDOWN,
+        now,
+    );
+
+    let resolutions = resolve_batch(
+        &mut game,
+        vec![queued(PlayerColor::White, a1, a5, now)],
+        now,
+    );
+
+    assert!(matches!(
+        resolutions.as_slice(),
+        [Resolution::Moved(_, outcome)] if outcome.captured == Some(ChessPiece::N)
+    ));
+}
+
+#[test]
+fn same_player_moves_to_one_square_do_not_bounce() {
+    let now = Instant::now();
+    let (a1, h4, a4) = (
+        (Rank::A, File::One),
+        (Rank::H, File::Four),
+        (Rank::A, File::Four),
+    );
+    let mut game = common::game_with(
+        &[
+            (a1, ChessPiece::R, PlayerColor::White),
+            (h4, ChessPiece::R, PlayerColor::White),
+        ],
+        DEFAULT_COOLDOWN,
+        now,
+    );
+    let second = queued(PlayerColor::White, h4, a4, now);
+
+    let resolutions = resolve_batch(
+        &mut game,
+        vec![queued(PlayerColor::White, a1, a4, now), second],
+        now,
+    );
+
+    assert_eq!(
+        resolutions.last(),
+        Some(&Resolution::Rejected(second, MoveError::SelfCapture))
+    );
+    assert_eq!(
+        piece_at(&game, a4),
+        Some((ChessPiece::R, PlayerColor::White))
+    );
+}
+
+#[test]
+fn illegal_moves_do_not_cause_conflicts() {
+    let now = Instant::now();
+    let (a1, a2, h4, a4) = (
+        (Rank::A, File::One),
+        (Rank::A, File::Two),
+        (Rank::H, File::Four),
+        (Rank::A, File::Four),
+    );
+    let mut game = common::game_with(
+        &[
+            (a1, ChessPiece::R, PlayerColor::White),
+            (a2, ChessPiece::Pawn, PlayerColor::White),
+            (h4, ChessPiece::R, PlayerColor::Black),
+        ],
+        DEFAULT_COOLDOWN,
+        now,
+    );
+    let blocked = queued(PlayerColor::White, a1, a4, now);
+
+    let resolutions = resolve_batch(
+        &mut game,
+        vec![blocked, queued(PlayerColor::Black, h4, a4, now)],
+        now,
+    );
+
+    assert!(resolutions.contains(&Resolution::Rejected(
+        blocked,
+        MoveError::Blocked { by: a2 }
+    )));
+    assert_eq!(
+        piece_at(&game, a4),
+        Some((ChessPiece::R, PlayerColor::Black))
+    );
+}
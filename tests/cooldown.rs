@@ -0,0 +1,346 @@
+use real_time_chess::{
+    ChessPiece, CooldownRule, File, Fraction, Location, MAX_COOLDOWN, PenaltyFactors, PlayerColor,
+    Rank, ReadyStage,
+    rules::{
+        Board, BoardPiece, Clock, GameState, MOVE_SETTLE, ManualClock, MoveError, PenaltyCooldown,
+        PremoveQueue, QueuedMove,
+    },
+};
+use std::time::Duration;
+
+const COOLDOWN: Duration = Duration::from_secs(4);
+const NANO: Duration = Duration::from_nanos(1);
+
+const A1: Location = (Rank::A, File::One);
+const A2: Location = (Rank::A, File::Two);
+const A3: Location = (Rank::A, File::Three);
+const B1: Location = (Rank::B, File::One);
+const H1: Location = (Rank::H, File::One);
+const H2: Location = (Rank::H, File::Two);
+
+/// white rooks on a1 and h1 and a white knight on b1, none of which have moved.
+fn pieces(clock: &ManualClock) -> Board {
+    let mut board = Board::empty();
+
+    for (square, piece) in [
+        (A1, ChessPiece::R),
+        (H1, ChessPiece::R),
+        (B1, ChessPiece::N),
+    ] {
+        board[&square] = Some(BoardPiece::new(piece, PlayerColor::White, clock.now()));
+    }
+
+    board
+}
+
+/// a game with the default penalty rules where every piece starts on `COOLDOWN`, and a clock
+/// that has just seen the rook on a1 move to a2 and settle.
+fn moved_rook(clock: &ManualClock) -> GameState {
+    let mut game = GameState::new(pieces(clock), COOLDOWN);
+    let outcome = game
+        .apply_move(PlayerColor::White, A1, A2, None, clock.now())
+        .unwrap();
+
+    assert_eq!(outcome.cooldown, COOLDOWN);
+    clock.advance(MOVE_SETTLE);
+
+    game
+}
+
+fn move_rook(game: &mut GameState, clock: &ManualClock) -> Result<Duration, MoveError> {
+    game.apply_move(PlayerColor::White, A2, A3, None, clock.now())
+        .map(|outcome| outcome.cooldown)
+}
+
+#[test]
+fn unmoved_pieces_are_ready() {
+    let clock = ManualClock::new();
+    let game = GameState::new(pieces(&clock), COOLDOWN);
+
+    assert!(game.is_ready(&A1, clock.now()));
+    assert!(!game.on_cooldown(&A1, clock.now()));
+    assert!(game.cooldowns(clock.now()).is_empty());
+}
+
+#[test]
+fn piece_is_locked_for_three_quarters_of_its_cooldown() {
+    let clock = ManualClock::new();
+    let game = moved_rook(&clock);
+
+    clock.advance(COOLDOWN / 4 * 3 - NANO);
+    assert!(game.on_cooldown(&A2, clock.now()));
+
+    clock.advance(NANO);
+    assert!(!game.on_cooldown(&A2, clock.now()));
+}
+
+#[test]
+fn cooldown_starts_once_the_piece_settles() {
+    let clock = ManualClock::new();
+    let game = moved_rook(&clock);
+
+    assert_eq!(
+        game.cooldowns(clock.now()),
+        vec![(A2, clock.now() + COOLDOWN)]
+    );
+}
+
+#[test]
+fn piece_is_ready_exactly_when_its_cooldown_ends() {
+    let clock = ManualClock::new();
+    let game = moved_rook(&clock);
+
+    clock.advance(COOLDOWN - NANO);
+    assert!(!game.is_ready(&A2, clock.now()));
+
+    clock.advance(NANO);
+    assert!(game.is_ready(&A2, clock.now()));
+    assert!(game.cooldowns(clock.now()).is_empty());
+}
+
+#[test]
+fn pieces_are_reported_ready_once_per_stage() {
+    let clock = ManualClock::new();
+    let game = moved_rook(&clock);
+    let start = clock.now();
+
+    clock.advance(COOLDOWN / 4 * 3 - NANO);
+    assert!(game.readied(start, clock.now()).is_empty());
+
+    let before = clock.now();
+    clock.advance(NANO);
+    assert_eq!(
+        game.readied(before, clock.now()),
+        vec![(A2, ReadyStage::Unlocked)]
+    );
+
+    let unlocked = clock.now();
+    clock.advance(COOLDOWN / 4 - NANO);
+    assert!(game.readied(unlocked, clock.now()).is_empty());
+
+    clock.advance(NANO);
+    assert_eq!(
+        game.readied(unlocked, clock.now()),
+        vec![(A2, ReadyStage::Ready)]
+    );
+
+    // checking rarely enough to miss the unlock only reports the piece as ready.
+    assert_eq!(
+        game.readied(start, clock.now()),
+        vec![(A2, ReadyStage::Ready)]
+    );
+}
+
+#[test]
+fn moving_a_locked_piece_is_rejected_and_grows_the_cooldown_by_a_third() {
+    let clock = ManualClock::new();
+    let mut game = moved_rook(&clock);
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(
+        move_rook(&mut game, &clock),
+        Err(MoveError::OnCooldown {
+            remaining: Duration::from_secs(2),
+        })
+    );
+
+    clock.advance(Duration::from_secs(3));
+    assert_eq!(move_rook(&mut game, &clock), Ok(COOLDOWN + COOLDOWN / 3));
+}
+
+#[test]
+fn penalties_never_grow_the_cooldown_past_the_longest_allowed() {
+    let clock = ManualClock::new();
+    let factors = PenaltyFactors {
+        rejected: Fraction::new(u32::MAX, 1),
+        ..PenaltyFactors::default()
+    };
+    let mut game = GameState::with_policy(
+        pieces(&clock),
+        Box::new(PenaltyCooldown::new(COOLDOWN, factors)),
+    );
+    game.apply_move(PlayerColor::White, A1, A2, None, clock.now())
+        .unwrap();
+    clock.advance(MOVE_SETTLE);
+
+    for _ in 0..3 {
+        assert!(move_rook(&mut game, &clock).is_err());
+    }
+
+    clock.advance(COOLDOWN);
+    assert_eq!(move_rook(&mut game, &clock), Ok(MAX_COOLDOWN));
+}
+
+#[test]
+fn moving_in_the_penalty_window_grows_the_cooldown_by_a_quarter() {
+    let clock = ManualClock::new();
+    let mut game = moved_rook(&clock);
+
+    clock.advance(COOLDOWN / 4 * 3 + NANO);
+    assert_eq!(move_rook(&mut game, &clock), Ok(COOLDOWN + COOLDOWN / 4));
+
+    // the player's cooldown never goes back down, whichever piece moves next.
+    clock.advance(COOLDOWN);
+    assert_eq!(
+        game.apply_move(PlayerColor::White, H1, H2, None, clock.now())
+            .map(|outcome| outcome.cooldown),
+        Ok(COOLDOWN + COOLDOWN / 4)
+    );
+}
+
+#[test]
+fn penalty_window_excludes_its_edges() {
+    let clock = ManualClock::new();
+    let mut game = moved_rook(&clock);
+
+    clock.advance(COOLDOWN / 4 * 3);
+    assert_eq!(move_rook(&mut game, &clock), Ok(COOLDOWN));
+
+    let clock = ManualClock::new();
+    let mut game = moved_rook(&clock);
+
+    clock.advance(COOLDOWN);
+    assert_eq!(move_rook(&mut game, &clock), Ok(COOLDOWN));
+}
+
+#[test]
+fn flat_cooldown_locks_the_whole_cooldown_without_penalties() {
+    let clock = ManualClock::new();
+    let rule = CooldownRule::Flat { cooldown: COOLDOWN };
+    let mut game = GameState::with_policy(pieces(&clock), rule.policy());
+
+    game.apply_move(PlayerColor::White, A1, A2, None, clock.now())
+        .unwrap();
+    clock.advance(MOVE_SETTLE + COOLDOWN - NANO);
+    assert!(game.on_cooldown(&A2, clock.now()));
+    assert_eq!(
+        move_rook(&mut game, &clock),
+        Err(MoveError::OnCooldown { remaining: NANO })
+    );
+
+    clock.advance(NANO);
+    assert_eq!(move_rook(&mut game, &clock), Ok(COOLDOWN));
+}
+
+#[test]
+fn per_piece_cooldowns_depend_on_the_piece() {
+    let clock = ManualClock::new();
+    let rule = CooldownRule::PerPiece {
+        pawn: Duration::from_secs(1),
+        knight: Duration::from_secs(2),
+        bishop: Duration::from_secs(3),
+        rook: Duration::from_secs(4),
+        queen: Duration::from_secs(5),
+        king: Duration::from_secs(6),
+    };
+    let mut game = GameState::with_policy(pieces(&clock), rule.policy());
+    let c3 = (Rank::C, File::Three);
+
+    let rook = game.apply_move(PlayerColor::White, A1, A2, None, clock.now());
+    let knight = game.apply_move(PlayerColor::White, B1, c3, None, clock.now());
+
+    assert_eq!(
+        rook.map(|outcome| outcome.cooldown),
+        Ok(Duration::from_secs(4))
+    );
+    assert_eq!(
+        knight.map(|outcome| outcome.cooldown),
+        Ok(Duration::from_secs(2))
+    );
+}
+
+#[test]
+fn stamina_runs_out_and_recovers() {
+    let clock = ManualClock::new();
+    let recovery = Duration::from_secs(2);
+    let rule = CooldownRule::Stamina {
+        cooldown: Duration::from_millis(500),
+        capacity: 2,
+        recovery,
+    };
+    let mut game = GameState::with_policy(pieces(&clock), rule.policy());
+
+    game.apply_move(PlayerColor::White, A1, A2, None, clock.now())
+        .unwrap();
+    game.apply_move(PlayerColor::White, H1, H2, None, clock.now())
+        .unwrap();
+
+    // the rook is off its own cooldown but the pool is empty until a point comes back.
+    clock.advance(MOVE_SETTLE + Duration::from_millis(500));
+    assert_eq!(
+        move_rook(&mut game, &clock),
+        Err(MoveError::OnCooldown {
+            remaining: recovery - MOVE_SETTLE - Duration::from_millis(500),
+        })
+    );
+
+    clock.advance(recovery - MOVE_SETTLE - Duration::from_millis(500) - NANO);
+    assert!(game.on_cooldown(&A2, clock.now()));
+
+    clock.advance(NANO);
+    assert_eq!(move_rook(&mut game, &clock), Ok(Duration::from_millis(500)));
+}
+
+#[test]
+fn premove_fires_the_moment_its_piece_is_ready() {
+    let clock = ManualClock::new();
+    let mut game = moved_rook(&clock);
+    let mut premoves = PremoveQueue::default();
+
+    premoves.queue(QueuedMove {
+        sender: 0u8,
+        player: PlayerColor::White,
+        from: A2,
+        to: A3,
+        promotion: None,
+        received: clock.now(),
+    });
+
+    // unlocking isn't enough, firing in the penalty window would cost the early penalty.
+    clock.advance(COOLDOWN / 4 * 3);
+    let (ready, dropped) = premoves.take_ready(&game, clock.now());
+    assert!(ready.is_empty() && dropped.is_empty());
+
+    clock.advance(COOLDOWN / 4 - NANO);
+    let (ready, dropped) = premoves.take_ready(&game, clock.now());
+    assert!(ready.is_empty() && dropped.is_empty());
+
+    clock.advance(NANO);
+    let (ready, dropped) = premoves.take_ready(&game, clock.now());
+    assert_eq!(ready.len(), 1);
+    assert!(dropped.is_empty());
+    assert_eq!(move_rook(&mut game, &clock), Ok(COOLDOWN));
+}
+
+#[test]
+fn premoves_dont_pass_to_whoever_takes_the_seat_next() {
+    let clock = ManualClock::new();
+    let game = moved_rook(&clock);
+    let mut premoves = PremoveQueue::default();
+    let premove = |sender: u8, from: Location, to: Location| QueuedMove {
+        sender,
+        player: PlayerColor::White,
+        from,
+        to,
+        promotion: None,
+        received: clock.now(),
+    };
+
+    premoves.queue(premove(1, A2, A3));
+    // white's first player leaves and someone else sits down and premoves another piece.
+    premoves.drop_sender(&1);
+    premoves.queue(premove(2, H1, H2));
+
+    clock.advance(COOLDOWN);
+    let (ready, dropped) = premoves.take_ready(&game, clock.now());
+
+    assert_eq!(
+        ready
+            .iter()
+            .map(|queued| (queued.sender, queued.from))
+            .collect::<Vec<_>>(),
+        vec![(2, H1)]
+    );
+    assert!(dropped.is_empty());
+    assert!(premoves.for_player(PlayerColor::White).is_empty());
+}
@@ -0,0 +1,160 @@
+use real_time_chess::{
+    ClientInGameMessage, ClientSystemMessage, DecodeError, FEATURES, Feature, File,
+    HandshakeRejection, HandshakeReply, PROTOCOL_VERSION, Premove, Rank, RequestId,
+    ServerSystemMessage, SystemError, SystemRequest, SystemResponse, decode,
+};
+
+#[test]
+fn decodes_what_bincode_encodes() {
+    let premove = Premove {
+        from: (Rank::E, File::Two),
+        to: (Rank::E, File::Four),
+        promotion: None,
+    };
+    let bytes = bincode::serialize(&ClientInGameMessage::Premove(premove)).unwrap();
+
+    assert!(matches!(
+        decode::<ClientInGameMessage>(&bytes),
+        Ok(ClientInGameMessage::Premove(decoded)) if decoded == premove
+    ));
+}
+
+#[test]
+fn empty_messages_are_rejected() {
+    assert_eq!(
+        decode::<ClientSystemMessage>(&[]).err(),
+        Some(DecodeError::Empty)
+    );
+}
+
+#[test]
+fn cut_short_messages_are_rejected() {
+    let bytes = bincode::serialize(&ClientInGameMessage::Premove(Premove {
+        from: (Rank::E, File::Two),
+        to: (Rank::E, File::Four),
+        promotion: None,
+    }))
+    .unwrap();
+
+    assert_eq!(
+        decode::<ClientInGameMessage>(&bytes[..bytes.len() - 1]).err(),
+        Some(DecodeError::Truncated)
+    );
+}
+
+#[test]
+fn leftover_bytes_are_rejected() {
+    let mut bytes = bincode::serialize(&ClientSystemMessage::ListRooms).unwrap();
+    bytes.push(0);
+
+    assert_eq!(
+        decode::<ClientSystemMessage>(&bytes).err(),
+        Some(DecodeError::TrailingBytes)
+    );
+}
+
+#[test]
+fn unknown_variants_are_rejected() {
+    let bytes = u32::MAX.to_le_bytes();
+
+    assert!(matches!(
+        decode::<ClientSystemMessage>(&bytes),
+        Err(DecodeError::Invalid(_))
+    ));
+}
+
+/// every version has to be able to read the handshake, so it has to stay the first variant.
+#[test]
+fn handshake_messages_come_first() {
+    let hello = bincode::serialize(&ClientSystemMessage::Hello {
+        version: PROTOCOL_VERSION,
+        features: FEATURES.to_vec(),
+    })
+    .unwrap();
+    let rejected = bincode::serialize(&ServerSystemMessage::Handshake(HandshakeReply::Rejected(
+        HandshakeRejection::UnsupportedVersion {
+            client: 0,
+            server: PROTOCOL_VERSION,
+        },
+    )))
+    .unwrap();
+
+    assert_eq!(hello[..4], 0u32.to_le_bytes());
+    assert_eq!(hello[4..8], PROTOCOL_VERSION.to_le_bytes());
+    assert_eq!(rejected[..4], 0u32.to_le_bytes());
+}
+
+/// the request ids can't get in front of the handshake, or older versions couldn't read it.
+#[test]
+fn handshake_is_sent_without_a_request_id() {
+    let hello = bincode::serialize(&SystemRequest::Hello {
+        version: PROTOCOL_VERSION,
+        features: FEATURES.to_vec(),
+    })
+    .unwrap();
+    let reply = HandshakeReply::Accepted {
+        features: FEATURES.to_vec(),
+    };
+    let accepted = bincode::serialize(&SystemResponse::Handshake(reply.clone())).unwrap();
+
+    assert_eq!(
+        hello,
+        bincode::serialize(&ClientSystemMessage::Hello {
+            version: PROTOCOL_VERSION,
+            features: FEATURES.to_vec(),
+        })
+        .unwrap()
+    );
+    assert_eq!(
+        accepted,
+        bincode::serialize(&ServerSystemMessage::Handshake(reply)).unwrap()
+    );
+}
+
+#[test]
+fn requests_and_replies_carry_their_id() {
+    let request = bincode::serialize(&SystemRequest::Request {
+        id: RequestId(3),
+        message: ClientSystemMessage::JoinRoom(['a', 'b', 'c', 'd']),
+    })
+    .unwrap();
+    let response = bincode::serialize(&SystemResponse::Reply {
+        request: Some(RequestId(3)),
+        message: ServerSystemMessage::Error(SystemError::NoSuchRoom),
+    })
+    .unwrap();
+
+    assert!(matches!(
+        decode::<SystemRequest>(&request),
+        Ok(SystemRequest::Request {
+            id: RequestId(3),
+            message: ClientSystemMessage::JoinRoom(['a', 'b', 'c', 'd']),
+        })
+    ));
+    assert!(matches!(
+        decode::<SystemResponse>(&response),
+        Ok(SystemResponse::Reply {
+            request: Some(RequestId(3)),
+            message: ServerSystemMessage::Error(SystemError::NoSuchRoom),
+        })
+    ));
+}
+
+/// a newer client can list features this build has never heard of without breaking the `Hello`.
+#[test]
+fn unknown_features_still_decode() {
+    let mut hello = bincode::serialize(&SystemRequest::Hello {
+        version: PROTOCOL_VERSION,
+        features: vec![Feature::Premoves, Feature::Premoves],
+    })
+    .unwrap();
+    // the second feature becomes one from the future.
+    let last = hello.len() - 4;
+    hello[last..].copy_from_slice(&99u32.to_le_bytes());
+
+    assert!(matches!(
+        decode::<SystemRequest>(&hello),
+        Ok(SystemRequest::Hello { features, .. })
+            if features == [Feature::Premoves, Feature::Unknown]
+    ));
+}
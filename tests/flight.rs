@@ -0,0 +1,259 @@
+use real_time_chess::{
+    ChessPiece, File, Location, PlayerColor, Rank,
+    rules::{Clock, GameState, ManualClock, MoveError},
+};
+use std::time::Duration;
+
+mod common;
+
+const COOLDOWN: Duration = Duration::from_secs(4);
+const PER_SQUARE: Duration = Duration::from_millis(100);
+const NANO: Duration = Duration::from_nanos(1);
+
+const A1: Location = (Rank::A, File::One);
+const A5: Location = (Rank::A, File::Five);
+const B8: Location = (Rank::B, File::Eight);
+const C6: Location = (Rank::C, File::Six);
+const E1: Location = (Rank::E, File::One);
+const F1: Location = (Rank::F, File::One);
+const F8: Location = (Rank::F, File::Eight);
+const G1: Location = (Rank::G, File::One);
+const G4: Location = (Rank::G, File::Four);
+const G7: Location = (Rank::G, File::Seven);
+const H1: Location = (Rank::H, File::One);
+const H4: Location = (Rank::H, File::Four);
+const H5: Location = (Rank::H, File::Five);
+const H7: Location = (Rank::H, File::Seven);
+const H8: Location = (Rank::H, File::Eight);
+
+/// a game with travel time and only `pieces` on the board, none of which have moved.
+fn travelling(clock: &ManualClock, pieces: &[(Location, ChessPiece, PlayerColor)]) -> GameState {
+    let mut game = common::game_with(pieces, COOLDOWN, clock.now());
+    game.travel_time = Some(PER_SQUARE);
+
+    game
+}
+
+fn piece_on(game: &GameState, square: &Location) -> Option<(ChessPiece, PlayerColor)> {
+    game.board[square].map(|piece| (piece.piece, piece.color))
+}
+
+#[test]
+fn flight_takes_longer_the_further_it_goes() {
+    let clock = ManualClock::new();
+    let mut game = travelling(&clock, &[(A1, ChessPiece::R, PlayerColor::White)]);
+
+    let outcome = game
+        .apply_move(PlayerColor::White, A1, A5, None, clock.now())
+        .unwrap();
+
+    assert_eq!(outcome.arrives, Some(clock.now() + PER_SQUARE * 4));
+    assert!(game.board[&A1].is_none() && game.board[&A5].is_none());
+
+    clock.advance(PER_SQUARE * 4 - NANO);
+    assert!(game.land_flights(clock.now()).is_empty());
+
+    clock.advance(NANO);
+    let landings = game.land_flights(clock.now());
+    assert_eq!(landings.len(), 1);
+    assert_eq!(
+        piece_on(&game, &A5),
+        Some((ChessPiece::R, PlayerColor::White))
+    );
+    assert!(game.flights().is_empty());
+}
+
+#[test]
+fn start_and_destination_are_reserved_until_landing() {
+    let clock = ManualClock::new();
+    let mut game = travelling(
+        &clock,
+        &[
+            (A1, ChessPiece::R, PlayerColor::White),
+            (H5, ChessPiece::R, PlayerColor::White),
+            (H1, ChessPiece::R, PlayerColor::Black),
+        ],
+    );
+
+    game.apply_move(PlayerColor::White, A1, A5, None, clock.now())
+        .unwrap();
+
+    assert_eq!(
+        game.apply_move(PlayerColor::White, H5, A5, None, clock.now()),
+        Err(MoveError::Reserved { square: A5 })
+    );
+    assert_eq!(
+        game.apply_move(PlayerColor::Black, H1, A1, None, clock.now()),
+        Err(MoveError::Reserved { square: A1 })
+    );
+
+    clock.advance(PER_SQUARE * 4);
+    game.land_flights(clock.now());
+    assert!(
+        game.apply_move(PlayerColor::Black, H1, A1, None, clock.now())
+            .is_ok()
+    );
+}
+
+#[test]
+fn landing_captures_the_enemy_piece_on_the_destination() {
+    let clock = ManualClock::new();
+    let mut game = travelling(
+        &clock,
+        &[
+            (A1, ChessPiece::R, PlayerColor::White),
+            (A5, ChessPiece::N, PlayerColor::Black),
+        ],
+    );
+
+    let outcome = game
+        .apply_move(PlayerColor::White, A1, A5, None, clock.now())
+        .unwrap();
+
+    // nothing is taken until the piece gets there.
+    assert_eq!(outcome.captured, None);
+    assert_eq!(
+        piece_on(&game, &A5),
+        Some((ChessPiece::N, PlayerColor::Black))
+    );
+
+    clock.advance(PER_SQUARE * 4);
+    let landing = game.land_flights(clock.now()).remove(0);
+
+    assert_eq!(landing.captured, Some(ChessPiece::N));
+    assert_eq!(landing.captured_at, Some(A5));
+    assert_eq!(
+        piece_on(&game, &A5),
+        Some((ChessPiece::R, PlayerColor::White))
+    );
+}
+
+#[test]
+fn landing_in_an_enemy_path_intercepts_it() {
+    let clock = ManualClock::new();
+    let mut game = travelling(
+        &clock,
+        &[
+            (H8, ChessPiece::R, PlayerColor::Black),
+            (G4, ChessPiece::R, PlayerColor::White),
+        ],
+    );
+
+    // the black rook crosses h4 four squares in, after the white rook has landed there.
+    game.apply_move(PlayerColor::Black, H8, H1, None, clock.now())
+        .unwrap();
+    game.apply_move(PlayerColor::White, G4, H4, None, clock.now())
+        .unwrap();
+
+    clock.advance(PER_SQUARE);
+    let landing = game.land_flights(clock.now()).remove(0);
+
+    assert_eq!(landing.intercepted.len(), 1);
+    assert_eq!(landing.intercepted[0].from, H8);
+    assert!(game.flights().is_empty());
+
+    clock.advance(PER_SQUARE * 6);
+    assert!(game.land_flights(clock.now()).is_empty());
+    assert!(game.board[&H1].is_none());
+}
+
+#[test]
+fn pieces_already_past_are_not_intercepted() {
+    let clock = ManualClock::new();
+    let mut game = travelling(
+        &clock,
+        &[
+            (H8, ChessPiece::R, PlayerColor::Black),
+            (G7, ChessPiece::R, PlayerColor::White),
+        ],
+    );
+
+    // the black rook is over h7 at the same moment the white rook lands there.
+    game.apply_move(PlayerColor::Black, H8, H1, None, clock.now())
+        .unwrap();
+    game.apply_move(PlayerColor::White, G7, H7, None, clock.now())
+        .unwrap();
+
+    clock.advance(PER_SQUARE);
+    assert!(game.land_flights(clock.now())[0].intercepted.is_empty());
+
+    clock.advance(PER_SQUARE * 6);
+    game.land_flights(clock.now());
+    assert_eq!(
+        piece_on(&game, &H1),
+        Some((ChessPiece::R, PlayerColor::Black))
+    );
+}
+
+#[test]
+fn friendly_pieces_and_knights_are_not_intercepted() {
+    let clock = ManualClock::new();
+    let mut game = travelling(
+        &clock,
+        &[
+            (H8, ChessPiece::R, PlayerColor::White),
+            (G4, ChessPiece::R, PlayerColor::White),
+            (B8, ChessPiece::N, PlayerColor::Black),
+        ],
+    );
+
+    game.apply_move(PlayerColor::White, H8, H1, None, clock.now())
+        .unwrap();
+    game.apply_move(PlayerColor::White, G4, H4, None, clock.now())
+        .unwrap();
+    game.apply_move(PlayerColor::Black, B8, C6, None, clock.now())
+        .unwrap();
+
+    clock.advance(PER_SQUARE);
+    assert!(
+        game.land_flights(clock.now())
+            .iter()
+            .all(|landing| landing.intercepted.is_empty())
+    );
+    assert_eq!(game.flights().len(), 2);
+
+    clock.advance(PER_SQUARE * 6);
+    game.land_flights(clock.now());
+    assert_eq!(
+        piece_on(&game, &H1),
+        Some((ChessPiece::R, PlayerColor::White))
+    );
+    assert_eq!(
+        piece_on(&game, &C6),
+        Some((ChessPiece::N, PlayerColor::Black))
+    );
+}
+
+#[test]
+fn castling_reserves_the_rook_squares_until_the_king_lands() {
+    let clock = ManualClock::new();
+    let mut game = travelling(
+        &clock,
+        &[
+            (E1, ChessPiece::K, PlayerColor::White),
+            (H1, ChessPiece::R, PlayerColor::White),
+            (F8, ChessPiece::R, PlayerColor::Black),
+        ],
+    );
+
+    game.apply_move(PlayerColor::White, E1, G1, None, clock.now())
+        .unwrap();
+
+    assert_eq!(
+        game.apply_move(PlayerColor::Black, F8, F1, None, clock.now()),
+        Err(MoveError::Reserved { square: F1 })
+    );
+
+    clock.advance(PER_SQUARE * 2);
+    let landing = game.land_flights(clock.now()).remove(0);
+
+    assert_eq!(landing.castle, Some((H1, F1)));
+    assert_eq!(
+        piece_on(&game, &G1),
+        Some((ChessPiece::K, PlayerColor::White))
+    );
+    assert_eq!(
+        piece_on(&game, &F1),
+        Some((ChessPiece::R, PlayerColor::White))
+    );
+}
@@ -0,0 +1,153 @@
+use real_time_chess::{
+    ChessPiece, File, Location, PlayerColor, Rank,
+    rules::{DEFAULT_COOLDOWN, MoveError},
+};
+use std::time::Instant;
+
+mod common;
+
+const A1: Location = (Rank::A, File::One);
+const A4: Location = (Rank::A, File::Four);
+const A6: Location = (Rank::A, File::Six);
+const A8: Location = (Rank::A, File::Eight);
+const B1: Location = (Rank::B, File::One);
+const B2: Location = (Rank::B, File::Two);
+const B3: Location = (Rank::B, File::Three);
+const C1: Location = (Rank::C, File::One);
+const C2: Location = (Rank::C, File::Two);
+const C3: Location = (Rank::C, File::Three);
+const C4: Location = (Rank::C, File::Four);
+const D4: Location = (Rank::D, File::Four);
+const D5: Location = (Rank::D, File::Five);
+const D6: Location = (Rank::D, File::Six);
+const E2: Location = (Rank::E, File::Two);
+const E3: Location = (Rank::E, File::Three);
+const E4: Location = (Rank::E, File::Four);
+const E5: Location = (Rank::E, File::Five);
+const E6: Location = (Rank::E, File::Six);
+const F3: Location = (Rank::F, File::Three);
+const G7: Location = (Rank::G, File::Seven);
+const H6: Location = (Rank::H, File::Six);
+const H8: Location = (Rank::H, File::Eight);
+
+/// moves the white piece on `from` to `to` on a board holding only `pieces`.
+fn white_move(
+    pieces: &[(Location, ChessPiece, PlayerColor)],
+    from: Location,
+    to: Location,
+) -> Result<(), MoveError> {
+    let now = Instant::now();
+    let mut game = common::game_with(pieces, DEFAULT_COOLDOWN, now);
+
+    game.apply_move(PlayerColor::White, from, to, None, now)
+        .map(|_| ())
+}
+
+fn lone(square: Location, piece: ChessPiece) -> [(Location, ChessPiece, PlayerColor); 1] {
+    [(square, piece, PlayerColor::White)]
+}
+
+#[test]
+fn king_steps_one_square_in_any_direction() {
+    let king = lone(D4, ChessPiece::K);
+
+    assert_eq!(white_move(&king, D4, D5), Ok(()));
+    assert_eq!(white_move(&king, D4, E5), Ok(()));
+    assert_eq!(
+        white_move(&king, D4, D6),
+        Err(MoveError::IllegalPattern(ChessPiece::K))
+    );
+}
+
+#[test]
+fn queen_slides_straight_and_diagonally() {
+    let queen = lone(D4, ChessPiece::Q);
+
+    assert_eq!(white_move(&queen, D4, A4), Ok(()));
+    assert_eq!(white_move(&queen, D4, H8), Ok(()));
+    assert_eq!(
+        white_move(&queen, D4, E6),
+        Err(MoveError::IllegalPattern(ChessPiece::Q))
+    );
+}
+
+#[test]
+fn bishop_only_slides_diagonally() {
+    let bishop = lone(C1, ChessPiece::B);
+
+    assert_eq!(white_move(&bishop, C1, H6), Ok(()));
+    assert_eq!(
+        white_move(&bishop, C1, C4),
+        Err(MoveError::IllegalPattern(ChessPiece::B))
+    );
+}
+
+#[test]
+fn rook_only_slides_straight() {
+    let rook = lone(A1, ChessPiece::R);
+
+    assert_eq!(white_move(&rook, A1, A8), Ok(()));
+    assert_eq!(
+        white_move(&rook, A1, B2),
+        Err(MoveError::IllegalPattern(ChessPiece::R))
+    );
+}
+
+#[test]
+fn knight_jumps_over_pieces() {
+    let knight = [
+        (B1, ChessPiece::N, PlayerColor::White),
+        (B2, ChessPiece::Pawn, PlayerColor::White),
+        (C2, ChessPiece::Pawn, PlayerColor::White),
+    ];
+
+    assert_eq!(white_move(&knight, B1, C3), Ok(()));
+    assert_eq!(
+        white_move(&knight, B1, B3),
+        Err(MoveError::IllegalPattern(ChessPiece::N))
+    );
+}
+
+#[test]
+fn pawn_only_moves_forward_without_a_capture() {
+    let pawn = lone(E2, ChessPiece::Pawn);
+
+    assert_eq!(white_move(&pawn, E2, E4), Ok(()));
+    assert_eq!(
+        white_move(&pawn, E2, F3),
+        Err(MoveError::IllegalPattern(ChessPiece::Pawn))
+    );
+}
+
+#[test]
+fn straight_slide_is_blocked_by_the_first_piece_in_the_way() {
+    let pieces = [
+        (A1, ChessPiece::R, PlayerColor::White),
+        (A4, ChessPiece::Pawn, PlayerColor::White),
+        (A6, ChessPiece::Pawn, PlayerColor::Black),
+    ];
+
+    assert_eq!(
+        white_move(&pieces, A1, A8),
+        Err(MoveError::Blocked { by: A4 })
+    );
+}
+
+#[test]
+fn diagonal_slide_is_blocked_by_either_color() {
+    let pieces = [
+        (C1, ChessPiece::B, PlayerColor::White),
+        (E3, ChessPiece::N, PlayerColor::Black),
+        (D4, ChessPiece::Q, PlayerColor::White),
+        (G7, ChessPiece::Pawn, PlayerColor::White),
+    ];
+
+    assert_eq!(
+        white_move(&pieces, C1, H6),
+        Err(MoveError::Blocked { by: E3 })
+    );
+    assert_eq!(
+        white_move(&pieces, D4, H8),
+        Err(MoveError::Blocked { by: G7 })
+    );
+}
@@ -0,0 +1,127 @@
+use real_time_chess::{
+    File, Location, PlayerColor, Rank,
+    rules::{Clock, ManualClock, MoveQueue, QueuedMove, move_queue::DEFAULT_INPUT_WINDOW},
+};
+use std::time::Duration;
+
+const WINDOW: Duration = DEFAULT_INPUT_WINDOW;
+const NANO: Duration = Duration::from_nanos(1);
+const STEP: Duration = Duration::from_millis(10);
+
+/// a move from `player`, told apart from the others by the file it starts on.
+fn queued(clock: &ManualClock, player: PlayerColor, file: File) -> QueuedMove<u8> {
+    let from: Location = (Rank::A, file);
+
+    QueuedMove {
+        sender: 0,
+        player,
+        from,
+        to: (Rank::B, file),
+        promotion: None,
+        received: clock.now(),
+    }
+}
+
+/// who sent each move of a batch and which file it starts on.
+fn order(batch: &[QueuedMove<u8>]) -> Vec<(PlayerColor, File)> {
+    batch
+        .iter()
+        .map(|queued| (queued.player, queued.from.1))
+        .collect()
+}
+
+#[test]
+fn batch_is_held_until_the_window_closes() {
+    let clock = ManualClock::new();
+    let mut queue = MoveQueue::new(WINDOW);
+
+    queue.push(queued(&clock, PlayerColor::White, File::One));
+    clock.advance(WINDOW / 2);
+    queue.push(queued(&clock, PlayerColor::Black, File::Two));
+
+    // the window opened with the first move, not the last.
+    clock.advance(WINDOW / 2 - NANO);
+    assert!(queue.take_batch(clock.now()).is_empty());
+
+    clock.advance(NANO);
+    assert_eq!(queue.take_batch(clock.now()).len(), 2);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn each_players_moves_keep_the_order_they_arrived_in() {
+    let clock = ManualClock::new();
+    let mut queue = MoveQueue::new(WINDOW);
+    let first = queued(&clock, PlayerColor::White, File::One);
+    clock.advance(STEP);
+    let second = queued(&clock, PlayerColor::White, File::Two);
+
+    // pushed out of order, sorted back by when they were received.
+    queue.push(second);
+    queue.push(first);
+    clock.advance(WINDOW);
+
+    assert_eq!(
+        order(&queue.take_batch(clock.now())),
+        vec![
+            (PlayerColor::White, File::One),
+            (PlayerColor::White, File::Two)
+        ]
+    );
+}
+
+#[test]
+fn players_take_turns_until_one_runs_out() {
+    let clock = ManualClock::new();
+    let mut queue = MoveQueue::new(WINDOW);
+
+    // black's move arrives first but arrival order between players doesn't count.
+    queue.push(queued(&clock, PlayerColor::Black, File::One));
+    for file in [File::Two, File::Three, File::Four] {
+        clock.advance(STEP);
+        queue.push(queued(&clock, PlayerColor::White, file));
+    }
+    clock.advance(WINDOW);
+
+    assert_eq!(
+        order(&queue.take_batch(clock.now())),
+        vec![
+            (PlayerColor::White, File::Two),
+            (PlayerColor::Black, File::One),
+            (PlayerColor::White, File::Three),
+            (PlayerColor::White, File::Four),
+        ]
+    );
+}
+
+#[test]
+fn first_pick_alternates_between_contested_batches() {
+    let clock = ManualClock::new();
+    let mut queue = MoveQueue::new(WINDOW);
+    let mut contested = || {
+        queue.push(queued(&clock, PlayerColor::White, File::One));
+        queue.push(queued(&clock, PlayerColor::Black, File::Two));
+        clock.advance(WINDOW);
+        queue.take_batch(clock.now())[0].player
+    };
+
+    assert_eq!(contested(), PlayerColor::White);
+    assert_eq!(contested(), PlayerColor::Black);
+    assert_eq!(contested(), PlayerColor::White);
+}
+
+#[test]
+fn uncontested_batches_dont_change_the_first_pick() {
+    let clock = ManualClock::new();
+    let mut queue = MoveQueue::new(WINDOW);
+
+    queue.push(queued(&clock, PlayerColor::White, File::One));
+    clock.advance(WINDOW);
+    queue.take_batch(clock.now());
+
+    queue.push(queued(&clock, PlayerColor::Black, File::One));
+    queue.push(queued(&clock, PlayerColor::White, File::Two));
+    clock.advance(WINDOW);
+
+    assert_eq!(queue.take_batch(clock.now())[0].player, PlayerColor::White);
+}
@@ -0,0 +1,299 @@
+use real_time_chess::{
+    ChessPiece, File, Location, PlayerColor, Rank,
+    rules::{
+        BoardPiece, Clock, DEFAULT_EN_PASSANT_WINDOW, GameState, MOVE_SETTLE, ManualClock,
+        MoveError,
+    },
+};
+use std::time::Duration;
+
+mod common;
+
+const COOLDOWN: Duration = Duration::from_secs(1);
+const NANO: Duration = Duration::from_nanos(1);
+
+const A1: Location = (Rank::A, File::One);
+const A7: Location = (Rank::A, File::Seven);
+const A8: Location = (Rank::A, File::Eight);
+const B1: Location = (Rank::B, File::One);
+const C1: Location = (Rank::C, File::One);
+const D5: Location = (Rank::D, File::Five);
+const D6: Location = (Rank::D, File::Six);
+const D7: Location = (Rank::D, File::Seven);
+const E1: Location = (Rank::E, File::One);
+const E2: Location = (Rank::E, File::Two);
+const E5: Location = (Rank::E, File::Five);
+const F1: Location = (Rank::F, File::One);
+const G1: Location = (Rank::G, File::One);
+const H1: Location = (Rank::H, File::One);
+const H2: Location = (Rank::H, File::Two);
+
+/// waits until every piece that has moved is ready again.
+fn wait(clock: &ManualClock) {
+    clock.advance(MOVE_SETTLE + COOLDOWN);
+}
+
+fn white_move(
+    game: &mut GameState,
+    clock: &ManualClock,
+    from: Location,
+    to: Location,
+    promotion: Option<ChessPiece>,
+) -> Result<(), MoveError> {
+    game.apply_move(PlayerColor::White, from, to, promotion, clock.now())
+        .map(|_| ())
+}
+
+/// a white king on e1 with both rooks, none of which have moved.
+fn castling(clock: &ManualClock) -> GameState {
+    common::game_with(
+        &[
+            (E1, ChessPiece::K, PlayerColor::White),
+            (A1, ChessPiece::R, PlayerColor::White),
+            (H1, ChessPiece::R, PlayerColor::White),
+        ],
+        COOLDOWN,
+        clock.now(),
+    )
+}
+
+#[test]
+fn pawn_promotes_to_a_queen_by_default() {
+    let clock = ManualClock::new();
+    let mut game = common::game_with(
+        &[(A7, ChessPiece::Pawn, PlayerColor::White)],
+        COOLDOWN,
+        clock.now(),
+    );
+
+    let outcome = game
+        .apply_move(PlayerColor::White, A7, A8, None, clock.now())
+        .unwrap();
+
+    assert_eq!(outcome.promotion, Some(ChessPiece::Q));
+    assert_eq!(
+        game.board[&A8].map(|piece| piece.piece),
+        Some(ChessPiece::Q)
+    );
+}
+
+#[test]
+fn pawn_promotes_to_the_piece_picked() {
+    let clock = ManualClock::new();
+    let mut game = common::game_with(
+        &[(A7, ChessPiece::Pawn, PlayerColor::White)],
+        COOLDOWN,
+        clock.now(),
+    );
+
+    let outcome = game
+        .apply_move(PlayerColor::White, A7, A8, Some(ChessPiece::N), clock.now())
+        .unwrap();
+
+    assert_eq!(outcome.promotion, Some(ChessPiece::N));
+    assert_eq!(
+        game.board[&A8].map(|piece| piece.piece),
+        Some(ChessPiece::N)
+    );
+}
+
+#[test]
+fn promoting_to_a_king_or_pawn_is_rejected() {
+    let clock = ManualClock::new();
+    let mut game = common::game_with(
+        &[(A7, ChessPiece::Pawn, PlayerColor::White)],
+        COOLDOWN,
+        clock.now(),
+    );
+
+    for piece in [ChessPiece::K, ChessPiece::Pawn] {
+        assert_eq!(
+            white_move(&mut game, &clock, A7, A8, Some(piece)),
+            Err(MoveError::InvalidPromotion(piece))
+        );
+    }
+
+    assert_eq!(
+        game.board[&A7].map(|piece| piece.piece),
+        Some(ChessPiece::Pawn)
+    );
+    assert!(game.board[&A8].is_none());
+}
+
+#[test]
+fn promotion_off_the_last_rank_is_rejected() {
+    let clock = ManualClock::new();
+    let mut game = common::game_with(
+        &[
+            (E2, ChessPiece::Pawn, PlayerColor::White),
+            (A1, ChessPiece::R, PlayerColor::White),
+        ],
+        COOLDOWN,
+        clock.now(),
+    );
+
+    assert_eq!(
+        white_move(
+            &mut game,
+            &clock,
+            E2,
+            (Rank::E, File::Three),
+            Some(ChessPiece::Q)
+        ),
+        Err(MoveError::InvalidPromotion(ChessPiece::Q))
+    );
+    // only pawns get promoted, even on the last rank.
+    assert_eq!(
+        white_move(&mut game, &clock, A1, A8, Some(ChessPiece::Q)),
+        Err(MoveError::InvalidPromotion(ChessPiece::Q))
+    );
+}
+
+#[test]
+fn en_passant_takes_the_pawn_that_stepped_past() {
+    let clock = ManualClock::new();
+    let mut game = common::game_with(
+        &[
+            (E5, ChessPiece::Pawn, PlayerColor::White),
+            (D7, ChessPiece::Pawn, PlayerColor::Black),
+        ],
+        COOLDOWN,
+        clock.now(),
+    );
+
+    game.apply_move(PlayerColor::Black, D7, D5, None, clock.now())
+        .unwrap();
+    clock.advance(DEFAULT_EN_PASSANT_WINDOW - NANO);
+
+    let outcome = game
+        .apply_move(PlayerColor::White, E5, D6, None, clock.now())
+        .unwrap();
+
+    assert_eq!(outcome.captured, Some(ChessPiece::Pawn));
+    assert_eq!(outcome.captured_at, Some(D5));
+    assert!(game.board[&D5].is_none());
+}
+
+#[test]
+fn en_passant_window_expires() {
+    let clock = ManualClock::new();
+    let mut game = common::game_with(
+        &[
+            (E5, ChessPiece::Pawn, PlayerColor::White),
+            (D7, ChessPiece::Pawn, PlayerColor::Black),
+        ],
+        COOLDOWN,
+        clock.now(),
+    );
+
+    game.apply_move(PlayerColor::Black, D7, D5, None, clock.now())
+        .unwrap();
+    clock.advance(DEFAULT_EN_PASSANT_WINDOW);
+
+    assert_eq!(
+        white_move(&mut game, &clock, E5, D6, None),
+        Err(MoveError::IllegalPattern(ChessPiece::Pawn))
+    );
+    assert!(game.board[&D5].is_some());
+}
+
+#[test]
+fn king_castles_with_an_unmoved_rook() {
+    let clock = ManualClock::new();
+    let mut game = castling(&clock);
+
+    let outcome = game
+        .apply_move(PlayerColor::White, E1, G1, None, clock.now())
+        .unwrap();
+
+    assert_eq!(outcome.castle, Some((H1, F1)));
+    assert_eq!(
+        game.board[&G1].map(|piece| piece.piece),
+        Some(ChessPiece::K)
+    );
+    assert_eq!(
+        game.board[&F1].map(|piece| piece.piece),
+        Some(ChessPiece::R)
+    );
+    assert!(game.board[&H1].is_none());
+}
+
+#[test]
+fn castling_through_a_piece_is_refused() {
+    let clock = ManualClock::new();
+    let mut game = castling(&clock);
+    game.board[&B1] = Some(BoardPiece::new(
+        ChessPiece::N,
+        PlayerColor::White,
+        clock.now(),
+    ));
+
+    // the knight isn't on the king's path but it's still between the king and the rook.
+    assert_eq!(
+        white_move(&mut game, &clock, E1, C1, None),
+        Err(MoveError::IllegalPattern(ChessPiece::K))
+    );
+}
+
+#[test]
+fn castling_after_the_rook_moved_is_refused() {
+    let clock = ManualClock::new();
+    let mut game = castling(&clock);
+
+    white_move(&mut game, &clock, H1, H2, None).unwrap();
+    wait(&clock);
+    white_move(&mut game, &clock, H2, H1, None).unwrap();
+    wait(&clock);
+
+    assert_eq!(
+        white_move(&mut game, &clock, E1, G1, None),
+        Err(MoveError::IllegalPattern(ChessPiece::K))
+    );
+    // the other rook hasn't moved so that side still works.
+    assert_eq!(white_move(&mut game, &clock, E1, C1, None), Ok(()));
+}
+
+#[test]
+fn castling_after_the_king_moved_is_refused() {
+    let clock = ManualClock::new();
+    let mut game = castling(&clock);
+
+    white_move(&mut game, &clock, E1, E2, None).unwrap();
+    wait(&clock);
+    white_move(&mut game, &clock, E2, E1, None).unwrap();
+    wait(&clock);
+
+    for to in [G1, C1] {
+        assert_eq!(
+            white_move(&mut game, &clock, E1, to, None),
+            Err(MoveError::IllegalPattern(ChessPiece::K))
+        );
+    }
+}
+
+#[test]
+fn capturing_the_king_wins_the_game() {
+    let clock = ManualClock::new();
+    let mut game = common::game_with(
+        &[
+            (A1, ChessPiece::R, PlayerColor::White),
+            (H1, ChessPiece::R, PlayerColor::White),
+            (A8, ChessPiece::K, PlayerColor::Black),
+        ],
+        COOLDOWN,
+        clock.now(),
+    );
+
+    let outcome = game
+        .apply_move(PlayerColor::White, A1, A8, None, clock.now())
+        .unwrap();
+
+    assert_eq!(outcome.captured, Some(ChessPiece::K));
+    assert_eq!(game.winner(), Some(PlayerColor::White));
+    assert_eq!(
+        white_move(&mut game, &clock, H1, H2, None),
+        Err(MoveError::GameOver {
+            winner: PlayerColor::White,
+        })
+    );
+}
@@ -0,0 +1,128 @@
+use real_time_chess::{
+    ConfigError, CooldownRule, Fraction, MAX_COOLDOWN, MAX_TRAVEL_TIME, MIN_COOLDOWN,
+    MIN_TIME_LIMIT, PenaltyFactors, RoomConfig, TimeControl, Variant,
+};
+use std::time::Duration;
+
+const NANO: Duration = Duration::from_nanos(1);
+
+fn with_cooldown(cooldown: CooldownRule) -> RoomConfig {
+    RoomConfig {
+        cooldown,
+        ..RoomConfig::default()
+    }
+}
+
+fn with_factors(factors: PenaltyFactors) -> RoomConfig {
+    with_cooldown(CooldownRule::Penalty {
+        base: MIN_COOLDOWN,
+        factors,
+    })
+}
+
+#[test]
+fn cooldowns_have_to_be_in_range() {
+    for cooldown in [MIN_COOLDOWN - NANO, MAX_COOLDOWN + NANO] {
+        assert_eq!(
+            with_cooldown(CooldownRule::Flat { cooldown }).validate(),
+            Err(ConfigError::CooldownOutOfRange(cooldown))
+        );
+    }
+
+    for cooldown in [MIN_COOLDOWN, MAX_COOLDOWN] {
+        assert_eq!(
+            with_cooldown(CooldownRule::Flat { cooldown }).validate(),
+            Ok(())
+        );
+    }
+}
+
+#[test]
+fn penalty_fractions_cant_divide_by_zero() {
+    let factors = PenaltyFactors {
+        early: Fraction::new(1, 0),
+        ..PenaltyFactors::default()
+    };
+
+    assert_eq!(
+        with_factors(factors).validate(),
+        Err(ConfigError::BadFraction(Fraction::new(1, 0)))
+    );
+}
+
+#[test]
+fn penalty_fractions_cant_be_more_than_one() {
+    let too_big = Fraction::new(3, 2);
+    let factors = [
+        PenaltyFactors {
+            locked: too_big,
+            ..PenaltyFactors::default()
+        },
+        PenaltyFactors {
+            rejected: too_big,
+            ..PenaltyFactors::default()
+        },
+        PenaltyFactors {
+            early: too_big,
+            ..PenaltyFactors::default()
+        },
+    ];
+
+    for factors in factors {
+        assert_eq!(
+            with_factors(factors).validate(),
+            Err(ConfigError::BadFraction(too_big))
+        );
+    }
+
+    let whole = Fraction::new(1, 1);
+    let factors = PenaltyFactors {
+        locked: whole,
+        rejected: whole,
+        early: whole,
+    };
+    assert_eq!(with_factors(factors).validate(), Ok(()));
+}
+
+#[test]
+fn stamina_needs_at_least_one_move() {
+    let stamina = CooldownRule::Stamina {
+        cooldown: MIN_COOLDOWN,
+        capacity: 0,
+        recovery: MIN_COOLDOWN,
+    };
+
+    assert_eq!(
+        with_cooldown(stamina).validate(),
+        Err(ConfigError::NoStamina)
+    );
+}
+
+#[test]
+fn travel_time_has_to_be_in_range() {
+    for per_square in [Duration::ZERO, MAX_TRAVEL_TIME + NANO] {
+        let config = RoomConfig {
+            variant: Variant::Travel { per_square },
+            ..RoomConfig::default()
+        };
+
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::TravelTimeOutOfRange(per_square))
+        );
+    }
+}
+
+#[test]
+fn time_limit_cant_be_too_short() {
+    let limit = MIN_TIME_LIMIT - NANO;
+    let config = RoomConfig {
+        time_control: TimeControl::Limit(limit),
+        ..RoomConfig::default()
+    };
+
+    assert_eq!(
+        config.validate(),
+        Err(ConfigError::TimeLimitTooShort(limit))
+    );
+}
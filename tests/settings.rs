@@ -0,0 +1,121 @@
+use real_time_chess::{
+    RoomConfig, SpectatorPolicy,
+    rules::move_queue::DEFAULT_INPUT_WINDOW,
+    settings::{
+        LogLevel, Settings, SettingsError, load_settings, parse_flag, server::ServerSettings,
+    },
+};
+use serde::Deserialize;
+use std::time::Duration;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TestSettings {
+    port: u16,
+    log_level: LogLevel,
+    room: RoomConfig,
+}
+
+impl Settings for TestSettings {
+    fn set(&mut self, flag: &str, value: &str) -> Result<(), SettingsError> {
+        match flag {
+            "port" => self.port = parse_flag(flag, value)?,
+            "log-level" => self.log_level = parse_flag(flag, value)?,
+            _ => return Err(SettingsError::UnknownFlag(format!("--{flag}"))),
+        }
+
+        Ok(())
+    }
+}
+
+fn load(args: &[&str]) -> Result<TestSettings, SettingsError> {
+    load_settings(args.iter().map(|arg| arg.to_string()))
+}
+
+fn load_server(args: &[&str]) -> Result<ServerSettings, SettingsError> {
+    load_settings(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn flags_override_the_config_file() {
+    let path = std::env::temp_dir().join(format!("rt-chess-settings-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{ "port": 6000, "log_level": "debug" }"#).unwrap();
+
+    let path = path.to_str().unwrap();
+    let from_file = load(&["--config", path]).unwrap();
+    let overridden = load(&["--port=7000", "--config", path]).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(from_file.port, 6000);
+    assert_eq!(from_file.log_level, LogLevel::Debug);
+    assert_eq!(overridden.port, 7000);
+    assert_eq!(overridden.log_level, LogLevel::Debug);
+}
+
+#[test]
+fn room_rules_left_out_keep_their_defaults() {
+    let path = std::env::temp_dir().join(format!("rt-chess-room-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{ "room": { "spectators": "Closed" } }"#).unwrap();
+
+    let settings = load(&["--config", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        settings.unwrap().room,
+        RoomConfig {
+            spectators: SpectatorPolicy::Closed,
+            ..RoomConfig::default()
+        }
+    );
+}
+
+#[test]
+fn settings_left_out_keep_their_defaults() {
+    let settings = load(&["--log-level", "WARN"]).unwrap();
+
+    assert_eq!(settings.port, 0);
+    assert_eq!(settings.log_level, LogLevel::Warn);
+}
+
+#[test]
+fn switches_dont_take_a_value() {
+    let followed = load_server(&["--dashboard", "--port", "80"]).unwrap();
+    let last = load_server(&["--port", "80", "--dashboard"]).unwrap();
+    let turned_off = load_server(&["--dashboard=false"]).unwrap();
+
+    assert!(followed.dashboard);
+    assert_eq!(followed.port, 80);
+    assert!(last.dashboard);
+    assert_eq!(last.port, 80);
+    assert!(!turned_off.dashboard);
+}
+
+#[test]
+fn bad_flags_are_reported() {
+    assert!(matches!(
+        load(&["--capacity", "3"]),
+        Err(SettingsError::UnknownFlag(flag)) if flag == "--capacity"
+    ));
+    assert!(matches!(
+        load(&["--port"]),
+        Err(SettingsError::MissingValue(flag)) if flag == "--port"
+    ));
+    assert!(matches!(
+        load(&["--port", "lots"]),
+        Err(SettingsError::BadValue { flag, .. }) if flag == "port"
+    ));
+    assert!(matches!(load(&["-h"]), Err(SettingsError::Help)));
+}
+
+#[test]
+fn default_room_rules_are_playable() {
+    assert_eq!(RoomConfig::default().validate(), Ok(()));
+}
+
+#[test]
+fn input_window_is_given_in_milliseconds() {
+    let settings = load_server(&["--input-window", "40"]).unwrap();
+
+    assert_eq!(settings.input_window, Duration::from_millis(40));
+    assert_eq!(load_server(&[]).unwrap().input_window, DEFAULT_INPUT_WINDOW);
+}